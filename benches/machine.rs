@@ -0,0 +1,34 @@
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use haneul::builtin::get_builtin;
+use haneul::machine::{Machine, StackFrame};
+use haneul::parser::program;
+use haneul::program::Program;
+
+fn load(path: &str) -> Program {
+  let data = fs::read(path).unwrap();
+  program(&data[..]).unwrap().1
+}
+
+fn run(program: &Program) {
+  let mut josa_table = program.josa_table.clone();
+  let builtin = get_builtin(&mut josa_table, &program.global_var_names);
+  let mut machine = Machine::new(builtin, program.global_var_names.clone(), josa_table);
+  let frame = StackFrame::new(program.code.clone(), program.const_table.clone());
+  assert!(machine.run(frame).is_ok());
+}
+
+fn recursion(c: &mut Criterion) {
+  let fibonacci = load("tests/fixtures/fibonacci.hnc");
+  c.bench_function("피보나치 20", |b| b.iter(|| run(&fibonacci)));
+}
+
+fn partial_application(c: &mut Criterion) {
+  let partial_sum = load("tests/fixtures/partial_sum.hnc");
+  c.bench_function("부분 적용 10000번", |b| b.iter(|| run(&partial_sum)));
+}
+
+criterion_group!(benches, recursion, partial_application);
+criterion_main!(benches);
@@ -1,30 +1,230 @@
+use std::rc::Rc;
+
 use crate::constant::Constant;
-use crate::funcobject::FuncObject;
+use crate::error::HaneulError;
+use crate::funcobject::{FuncObject, NativeFunction};
+use crate::io::Io;
+use crate::josa::{JosaMap, JosaTable};
+
+mod convert;
+mod file;
+mod format;
+mod jamo;
+mod math;
+mod numeral;
+
+fn make_native(
+  josa_table: &mut JosaTable,
+  name: &'static str,
+  josa_list: Vec<&str>,
+  variadic: bool,
+  function: NativeFunction,
+) -> Constant {
+  Constant::Function {
+    josa_map: Rc::new(JosaMap::new(
+      josa_list.into_iter().map(|josa| josa_table.intern(josa)),
+    )),
+    rest_args: if variadic { Some(Rc::default()) } else { None },
+    func_object: FuncObject::NativeFunc { name, function },
+  }
+}
 
-use indexmap::IndexMap;
+fn invalid_argument(function: &'static str, value: &Constant) -> HaneulError {
+  HaneulError::InvalidArgument {
+    function,
+    value: value.clone(),
+  }
+}
 
-fn make_josa_map(josa_list: Vec<&str>) -> IndexMap<String, Option<Constant>> {
-  let mut result = IndexMap::new();
-  for josa in josa_list {
-    result.insert(String::from(josa), None);
+fn domain_error(function: &'static str, value: &Constant) -> HaneulError {
+  HaneulError::DomainError {
+    function,
+    value: value.clone(),
   }
-  result
 }
 
-fn print_func(args: Vec<Constant>) -> Constant {
-  println!("{:?}", args[0]);
-  Constant::None
+/// 실수를 정수로 바꿉니다. 소수점 아래는 이미 정리되어 있어야 합니다.
+fn real_to_integer(
+  function: &'static str,
+  real: f64,
+  value: &Constant,
+) -> Result<Constant, HaneulError> {
+  if real.is_nan() {
+    Err(domain_error(function, value))
+  } else if real >= -(i64::MIN as f64) || real < i64::MIN as f64 {
+    Err(HaneulError::IntegerOverflow { function })
+  } else {
+    Ok(Constant::Integer(real as i64))
+  }
 }
 
-pub fn get_builtin() -> Vec<Option<Constant>> {
-  let print_object = FuncObject::NativeFunc {
-    function: print_func,
-  };
+fn print_func(io: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let mut values = vec![args[0].to_string()];
+  if let Constant::List(rest) = &args[1] {
+    values.extend(rest.iter().map(Constant::to_string));
+  }
+
+  io.write(&format!("{}\n", values.join(" ")))?;
+  Ok(Constant::None)
+}
 
-  let env = vec![Some(Constant::Function {
-    josa_map: make_josa_map(vec!["을"]),
-    func_object: print_object,
-  })];
+fn input_func(io: &mut Io, _: Vec<Constant>) -> Result<Constant, HaneulError> {
+  Ok(match io.read_line()? {
+    Some(line) => Constant::Str(line.into()),
+    None => Constant::None,
+  })
+}
+
+/// 한 줄을 읽어 앞뒤 공백을 빼고 `parse`로 바꿉니다.
+fn read_value<T: std::str::FromStr>(io: &mut Io, expected: &'static str) -> Result<T, HaneulError> {
+  let line = io.read_line()?.ok_or(HaneulError::EndOfInput)?;
+  line.trim().parse().map_err(|_| HaneulError::InvalidInput {
+    expected,
+    input: line,
+  })
+}
+
+fn input_integer_func(io: &mut Io, _: Vec<Constant>) -> Result<Constant, HaneulError> {
+  Ok(Constant::Integer(read_value(io, "정수")?))
+}
+
+fn input_real_func(io: &mut Io, _: Vec<Constant>) -> Result<Constant, HaneulError> {
+  Ok(Constant::Real(read_value(io, "실수")?))
+}
+
+fn input_char_func(io: &mut Io, _: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match io.read_char()? {
+    Some(value) => Ok(Constant::Char(value)),
+    None => Err(HaneulError::EndOfInput),
+  }
+}
+
+fn end_of_input_func(io: &mut Io, _: Vec<Constant>) -> Result<Constant, HaneulError> {
+  Ok(Constant::Boolean(io.at_end()?))
+}
 
-  env
+/// 내장 함수들의 조사를 `josa_table`에 등록하고, 모든 내장 함수를 돌려줍니다.
+pub fn builtin_functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "출력", vec!["을"], true, print_func),
+    make_native(josa_table, "입력", vec![], false, input_func),
+    make_native(josa_table, "정수_입력", vec![], false, input_integer_func),
+    make_native(josa_table, "실수_입력", vec![], false, input_real_func),
+    make_native(josa_table, "문자_입력", vec![], false, input_char_func),
+    make_native(josa_table, "입력_끝", vec![], false, end_of_input_func),
+  ]
+  .into_iter()
+  .chain(math::functions(josa_table))
+  .chain(convert::functions(josa_table))
+  .chain(jamo::functions(josa_table))
+  .chain(numeral::functions(josa_table))
+  .chain(format::functions(josa_table))
+  .chain(file::functions(josa_table))
+  .collect()
+}
+
+/// 내장 함수들의 조사를 `josa_table`에 등록하고, 전역 변수 순서대로 이름이 같은 내장 함수를 돌려줍니다.
+pub fn get_builtin(
+  josa_table: &mut JosaTable,
+  global_var_names: &[String],
+) -> Vec<Option<Constant>> {
+  let functions = builtin_functions(josa_table);
+  global_var_names
+    .iter()
+    .map(|var_name| {
+      functions
+        .iter()
+        .find(|function| {
+          matches!(
+            function,
+            Constant::Function {
+              func_object: FuncObject::NativeFunc { name, .. },
+              ..
+            } if name == var_name
+          )
+        })
+        .cloned()
+    })
+    .collect()
+}
+
+/// 내장 함수를 시험할 때 여러 모듈이 함께 쓰는 함수들입니다.
+#[cfg(test)]
+pub(crate) mod test_util {
+  use super::*;
+
+  use crate::io::MemoryOutput;
+
+  /// 이름이 `name`인 내장 함수를 바로 호출합니다.
+  pub(crate) fn call_native(
+    io: &mut Io,
+    name: &str,
+    args: Vec<Constant>,
+  ) -> Result<Constant, HaneulError> {
+    for value in builtin_functions(&mut JosaTable::new()) {
+      if let Constant::Function {
+        func_object:
+          FuncObject::NativeFunc {
+            name: native_name,
+            function,
+          },
+        ..
+      } = value
+      {
+        if native_name == name {
+          return function(io, args);
+        }
+      }
+    }
+    panic!("내장 함수 {}가 없습니다.", name);
+  }
+
+  /// 출력은 버리고 입력은 비어 있는 입출력입니다.
+  pub(crate) fn empty_io() -> Io {
+    Io::new(Box::new(MemoryOutput::new()), Box::new(std::io::empty()))
+  }
+
+  /// 빈 입출력으로 내장 함수를 호출합니다.
+  pub(crate) fn call(name: &str, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+    call_native(&mut empty_io(), name, args)
+  }
+
+  pub(crate) fn text(value: &str) -> Constant {
+    Constant::Str(value.into())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::test_util::call_native;
+  use super::*;
+
+  use crate::io::MemoryOutput;
+  use std::io::Cursor;
+
+  fn read(io: &mut Io, name: &str) -> Result<Constant, HaneulError> {
+    call_native(io, name, Vec::new())
+  }
+
+  #[test]
+  fn read_scripted_input() {
+    let input = Cursor::new("12\n 3.5 \r\n가나\n열둘\n");
+    let mut io = Io::new(Box::new(MemoryOutput::new()), Box::new(input));
+
+    assert_eq!(read(&mut io, "정수_입력").unwrap(), Constant::Integer(12));
+    assert_eq!(read(&mut io, "실수_입력").unwrap(), Constant::Real(3.5));
+    assert_eq!(read(&mut io, "문자_입력").unwrap(), Constant::Char('가'));
+    assert_eq!(read(&mut io, "입력").unwrap(), Constant::Str("나".into()));
+    assert!(matches!(
+      read(&mut io, "정수_입력"),
+      Err(HaneulError::InvalidInput { expected: "정수", input }) if input == "열둘"
+    ));
+
+    assert_eq!(read(&mut io, "입력_끝").unwrap(), Constant::Boolean(true));
+    assert_eq!(read(&mut io, "입력").unwrap(), Constant::None);
+    assert!(matches!(
+      read(&mut io, "문자_입력"),
+      Err(HaneulError::EndOfInput)
+    ));
+  }
 }
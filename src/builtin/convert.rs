@@ -0,0 +1,181 @@
+use std::convert::TryFrom;
+
+use super::{domain_error, invalid_argument, make_native, real_to_integer};
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::io::Io;
+use crate::josa::JosaTable;
+
+/// 실수는 소수점 아래를 버리고, 문자는 코드 포인트로, 부울은 1과 0으로 바꿉니다.
+fn to_integer_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match &args[0] {
+    Constant::Integer(v) => Ok(Constant::Integer(*v)),
+    Constant::Real(v) => real_to_integer("정수로", v.trunc(), &args[0]),
+    Constant::Char(v) => Ok(Constant::Integer(*v as i64)),
+    Constant::Boolean(v) => Ok(Constant::Integer(*v as i64)),
+    value => Err(invalid_argument("정수로", value)),
+  }
+}
+
+fn to_real_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match &args[0] {
+    Constant::Integer(v) => Ok(Constant::Real(*v as f64)),
+    Constant::Real(v) => Ok(Constant::Real(*v)),
+    value => Err(invalid_argument("실수로", value)),
+  }
+}
+
+/// 정수를 그 코드 포인트의 문자로 바꿉니다.
+fn to_char_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match &args[0] {
+    Constant::Integer(v) => u32::try_from(*v)
+      .ok()
+      .and_then(std::char::from_u32)
+      .map(Constant::Char)
+      .ok_or_else(|| domain_error("문자로", &args[0])),
+    Constant::Char(v) => Ok(Constant::Char(*v)),
+    value => Err(invalid_argument("문자로", value)),
+  }
+}
+
+fn to_string_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  Ok(Constant::Str(args[0].to_string().into()))
+}
+
+fn type_of_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  Ok(Constant::Str(args[0].type_name().into()))
+}
+
+/// 문자열의 앞뒤 공백을 빼고 `parse`로 해석합니다.
+fn parse_str<T: std::str::FromStr>(
+  function: &'static str,
+  expected: &'static str,
+  value: &Constant,
+) -> Result<T, HaneulError> {
+  match value {
+    Constant::Str(text) => text.trim().parse().map_err(|_| HaneulError::ParseFailed {
+      expected,
+      text: text.to_string(),
+    }),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+fn parse_integer_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  parse_str("정수_읽기", "정수", &args[0]).map(Constant::Integer)
+}
+
+/// `inf`나 `NaN`처럼 유한하지 않은 값은 해석하지 않습니다.
+fn parse_real_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let value: f64 = parse_str("실수_읽기", "실수", &args[0])?;
+  if value.is_finite() {
+    Ok(Constant::Real(value))
+  } else {
+    Err(HaneulError::ParseFailed {
+      expected: "실수",
+      text: args[0].to_string(),
+    })
+  }
+}
+
+pub(super) fn functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "정수로", vec!["을"], false, to_integer_func),
+    make_native(josa_table, "실수로", vec!["을"], false, to_real_func),
+    make_native(josa_table, "문자로", vec!["을"], false, to_char_func),
+    make_native(josa_table, "문자열로", vec!["을"], false, to_string_func),
+    make_native(josa_table, "종류", vec!["을"], false, type_of_func),
+    make_native(
+      josa_table,
+      "정수_읽기",
+      vec!["을"],
+      false,
+      parse_integer_func,
+    ),
+    make_native(josa_table, "실수_읽기", vec!["을"], false, parse_real_func),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use crate::builtin::test_util::{call, text};
+
+  #[test]
+  fn convert_values() {
+    use Constant::{Boolean, Char, Integer, Real};
+
+    assert_eq!(call("정수로", vec![Real(-2.7)]).unwrap(), Integer(-2));
+    assert_eq!(call("정수로", vec![Char('가')]).unwrap(), Integer(0xAC00));
+    assert_eq!(call("정수로", vec![Boolean(true)]).unwrap(), Integer(1));
+    assert_eq!(call("실수로", vec![Integer(3)]).unwrap(), Real(3.0));
+    assert_eq!(call("문자로", vec![Integer(0xAC01)]).unwrap(), Char('각'));
+
+    assert_eq!(call("문자열로", vec![Real(3.0)]).unwrap(), text("3.0"));
+    assert_eq!(
+      call("문자열로", vec![Boolean(false)]).unwrap(),
+      text("거짓")
+    );
+    let list = Constant::List(Rc::new(vec![Integer(1), Char('가'), text("나")]));
+    assert_eq!(
+      call("문자열로", vec![list.clone()]).unwrap(),
+      text("[1, 가, 나]")
+    );
+
+    assert_eq!(call("종류", vec![list]).unwrap(), text("목록"));
+    assert_eq!(call("종류", vec![Constant::None]).unwrap(), text("(없음)"));
+
+    assert_eq!(
+      call("정수_읽기", vec![text(" -42 ")]).unwrap(),
+      Integer(-42)
+    );
+    assert_eq!(
+      call("실수_읽기", vec![text("1.5e3")]).unwrap(),
+      Real(1500.0)
+    );
+  }
+
+  #[test]
+  fn report_conversion_errors() {
+    use Constant::{Integer, Real};
+
+    assert!(matches!(
+      call("정수로", vec![Real(1e30)]),
+      Err(HaneulError::IntegerOverflow {
+        function: "정수로"
+      })
+    ));
+    assert!(matches!(
+      call("문자로", vec![Integer(0xD800)]),
+      Err(HaneulError::DomainError {
+        function: "문자로",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("문자로", vec![Integer(-1)]),
+      Err(HaneulError::DomainError { .. })
+    ));
+    assert!(matches!(
+      call("실수로", vec![text("1.5")]),
+      Err(HaneulError::InvalidArgument {
+        function: "실수로",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("정수_읽기", vec![text("12개")]),
+      Err(HaneulError::ParseFailed { expected: "정수", text }) if text == "12개"
+    ));
+    assert!(matches!(
+      call("실수_읽기", vec![text("NaN")]),
+      Err(HaneulError::ParseFailed {
+        expected: "실수",
+        ..
+      })
+    ));
+  }
+}
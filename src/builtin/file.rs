@@ -0,0 +1,201 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{invalid_argument, make_native};
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::io::Io;
+use crate::josa::JosaTable;
+
+fn str_of<'a>(function: &'static str, value: &'a Constant) -> Result<&'a str, HaneulError> {
+  match value {
+    Constant::Str(text) => Ok(text),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+fn file_failed(path: &str, err: io::Error) -> HaneulError {
+  HaneulError::FileFailed {
+    path: path.to_string(),
+    reason: err.to_string(),
+  }
+}
+
+fn read_file_func(io: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let path = str_of("파일_읽기", &args[0])?;
+  io.permissions().check_read(path)?;
+
+  let text = fs::read_to_string(path).map_err(|err| file_failed(path, err))?;
+  Ok(Constant::Str(text.into()))
+}
+
+/// `append`가 참이면 파일 끝에 덧붙이고, 아니면 파일을 새로 씁니다. 파일이 없으면 만듭니다.
+fn write_file(
+  function: &'static str,
+  io: &mut Io,
+  args: Vec<Constant>,
+  append: bool,
+) -> Result<Constant, HaneulError> {
+  let path = str_of(function, &args[0])?;
+  let text = str_of(function, &args[1])?;
+  io.permissions().check_write(path)?;
+
+  OpenOptions::new()
+    .write(true)
+    .create(true)
+    .append(append)
+    .truncate(!append)
+    .open(path)
+    .and_then(|mut file| file.write_all(text.as_bytes()))
+    .map_err(|err| file_failed(path, err))?;
+  Ok(Constant::None)
+}
+
+fn write_file_func(io: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  write_file("파일_쓰기", io, args, false)
+}
+
+fn append_file_func(io: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  write_file("파일_덧붙이기", io, args, true)
+}
+
+/// 디렉터리 안의 이름들을 정렬한 목록입니다.
+fn list_dir_func(io: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let path = str_of("디렉터리_목록", &args[0])?;
+  io.permissions().check_read(path)?;
+
+  let mut names = fs::read_dir(path)
+    .and_then(|entries| {
+      entries
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<io::Result<Vec<String>>>()
+    })
+    .map_err(|err| file_failed(path, err))?;
+  names.sort();
+
+  let names = names.into_iter().map(|name| Constant::Str(name.into()));
+  Ok(Constant::List(Rc::new(names.collect())))
+}
+
+/// 있는지 확인하는 것도 읽기 권한이 필요합니다.
+fn exists_func(io: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let path = str_of("파일_있음", &args[0])?;
+  io.permissions().check_read(path)?;
+
+  Ok(Constant::Boolean(Path::new(path).exists()))
+}
+
+pub(super) fn functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "파일_읽기", vec!["을"], false, read_file_func),
+    make_native(
+      josa_table,
+      "파일_쓰기",
+      vec!["에", "을"],
+      false,
+      write_file_func,
+    ),
+    make_native(
+      josa_table,
+      "파일_덧붙이기",
+      vec!["에", "을"],
+      false,
+      append_file_func,
+    ),
+    make_native(
+      josa_table,
+      "디렉터리_목록",
+      vec!["을"],
+      false,
+      list_dir_func,
+    ),
+    make_native(josa_table, "파일_있음", vec!["을"], false, exists_func),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::env;
+
+  use crate::builtin::test_util::{call_native, empty_io, text};
+  use crate::permission::Permissions;
+
+  #[test]
+  fn access_allowed_files() {
+    let dir = env::temp_dir().join(format!("haneul-file-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| text(dir.join(name).to_str().unwrap());
+
+    let mut permissions = Permissions::new();
+    permissions.allow_read(&dir).unwrap();
+    permissions.allow_write(&dir).unwrap();
+    let mut io = empty_io();
+    io.set_permissions(permissions);
+    let mut call = |name: &str, args: Vec<Constant>| call_native(&mut io, name, args);
+
+    assert_eq!(
+      call("파일_있음", vec![path("결과.txt")]).unwrap(),
+      Constant::Boolean(false)
+    );
+    assert_eq!(
+      call("파일_쓰기", vec![path("결과.txt"), text("하늘\n")]).unwrap(),
+      Constant::None
+    );
+    call("파일_덧붙이기", vec![path("결과.txt"), text("바다\n")]).unwrap();
+    call("파일_쓰기", vec![path("a.txt"), text("")]).unwrap();
+
+    assert_eq!(
+      call("파일_읽기", vec![path("결과.txt")]).unwrap(),
+      text("하늘\n바다\n")
+    );
+    assert_eq!(
+      call("파일_있음", vec![path("결과.txt")]).unwrap(),
+      Constant::Boolean(true)
+    );
+    assert_eq!(
+      call("디렉터리_목록", vec![path("")]).unwrap(),
+      Constant::List(Rc::new(vec![text("a.txt"), text("결과.txt")]))
+    );
+    assert!(matches!(
+      call("파일_읽기", vec![path("없는 파일.txt")]),
+      Err(HaneulError::FileFailed { .. })
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn deny_without_permission() {
+    let dir = env::temp_dir().join(format!("haneul-file-denied-{}", std::process::id()));
+    let file = text(dir.join("a.txt").to_str().unwrap());
+
+    let mut permissions = Permissions::new();
+    permissions.allow_read(&dir).unwrap();
+    let mut io = empty_io();
+    io.set_permissions(permissions);
+
+    assert!(matches!(
+      call_native(&mut io, "파일_쓰기", vec![file.clone(), text("하늘")]),
+      Err(HaneulError::PermissionDenied {
+        access: "쓰기", ..
+      })
+    ));
+    assert!(!dir.exists());
+
+    io.set_permissions(Permissions::new());
+    assert!(matches!(
+      call_native(&mut io, "파일_있음", vec![file]),
+      Err(HaneulError::PermissionDenied {
+        access: "읽기", ..
+      })
+    ));
+    assert!(matches!(
+      call_native(&mut io, "파일_읽기", vec![Constant::Integer(1)]),
+      Err(HaneulError::InvalidArgument { .. })
+    ));
+  }
+}
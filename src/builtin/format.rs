@@ -0,0 +1,393 @@
+use super::{invalid_argument, make_native};
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::io::Io;
+use crate::josa::JosaTable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+  Left,
+  Right,
+  Center,
+}
+
+/// `{:*>8.2}`처럼 자리 안의 `:` 뒤에 적는 형식입니다. `[채울 문자][정렬][0][너비][.정밀도]` 순서로 씁니다.
+#[derive(Debug)]
+struct Spec {
+  fill: char,
+  align: Option<Align>,
+  zero: bool,
+  width: usize,
+  precision: Option<usize>,
+}
+
+fn to_align(c: char) -> Option<Align> {
+  match c {
+    '<' => Some(Align::Left),
+    '>' => Some(Align::Right),
+    '^' => Some(Align::Center),
+    _ => None,
+  }
+}
+
+/// 너비와 정밀도의 최댓값입니다. 큰 너비로 메모리를 한꺼번에 잡아먹지 않도록 막습니다.
+const MAX_WIDTH: usize = 1000;
+
+/// 숫자가 없으면 `None`이고, `MAX_WIDTH`보다 크면 에러입니다.
+fn parse_number(chars: &[char], index: &mut usize) -> Result<Option<usize>, String> {
+  let start = *index;
+  while *index < chars.len() && chars[*index].is_ascii_digit() {
+    *index += 1;
+  }
+  if start == *index {
+    return Ok(None);
+  }
+
+  let digits: String = chars[start..*index].iter().collect();
+  match digits.parse() {
+    Ok(number) if number <= MAX_WIDTH => Ok(Some(number)),
+    _ => Err(format!(
+      "너비와 정밀도는 {}보다 클 수 없습니다 : {}",
+      MAX_WIDTH, digits
+    )),
+  }
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, String> {
+  let chars: Vec<char> = spec.chars().collect();
+  let mut result = Spec {
+    fill: ' ',
+    align: None,
+    zero: false,
+    width: 0,
+    precision: None,
+  };
+
+  let mut index = 0;
+  if let Some(align) = chars.get(1).copied().and_then(to_align) {
+    result.fill = chars[0];
+    result.align = Some(align);
+    index = 2;
+  } else if let Some(align) = chars.first().copied().and_then(to_align) {
+    result.align = Some(align);
+    index = 1;
+  }
+
+  if chars.get(index) == Some(&'0') {
+    result.zero = true;
+    index += 1;
+  }
+  result.width = parse_number(&chars, &mut index)?.unwrap_or(0);
+  if chars.get(index) == Some(&'.') {
+    index += 1;
+    let precision = parse_number(&chars, &mut index)?;
+    result.precision = Some(precision.ok_or_else(|| format!("'{}'에 정밀도가 없습니다.", spec))?);
+  }
+
+  if index == chars.len() {
+    Ok(result)
+  } else {
+    Err(format!("'{}'은(는) 알 수 없는 형식입니다.", spec))
+  }
+}
+
+/// 값을 사람이 읽는 모양으로 적고 너비에 맞춰 채웁니다.
+/// 정밀도는 실수와 정수에는 소수점 아래 자릿수, 그 밖의 값에는 최대 글자 수입니다.
+fn render(value: &Constant, spec: &Spec) -> String {
+  let text = match (value, spec.precision) {
+    (Constant::Real(v), Some(precision)) => format!("{:.*}", precision, v),
+    (Constant::Integer(v), Some(0)) => v.to_string(),
+    (Constant::Integer(v), Some(precision)) => format!("{}.{}", v, "0".repeat(precision)),
+    (value, Some(precision)) => value.to_string().chars().take(precision).collect(),
+    (value, None) => value.to_string(),
+  };
+
+  let length = text.chars().count();
+  if length >= spec.width {
+    return text;
+  }
+  let padding = spec.width - length;
+  let numeric = matches!(value, Constant::Integer(_) | Constant::Real(_));
+
+  if spec.zero && numeric && spec.align.is_none() {
+    let (sign, digits) = match text.strip_prefix('-') {
+      Some(digits) => ("-", digits),
+      None => ("", &text[..]),
+    };
+    return format!("{}{}{}", sign, "0".repeat(padding), digits);
+  }
+
+  let fill = |count: usize| spec.fill.to_string().repeat(count);
+  let align = spec
+    .align
+    .unwrap_or(if numeric { Align::Right } else { Align::Left });
+  match align {
+    Align::Left => format!("{}{}", text, fill(padding)),
+    Align::Right => format!("{}{}", fill(padding), text),
+    Align::Center => format!(
+      "{}{}{}",
+      fill(padding / 2),
+      text,
+      fill(padding - padding / 2)
+    ),
+  }
+}
+
+/// `{`와 `}` 사이의 자리를 `lookup`으로 찾은 값으로 채웁니다. `{{`와 `}}`는 중괄호 하나로 씁니다.
+fn fill_template(
+  template: &str,
+  mut lookup: impl FnMut(&str) -> Result<Constant, String>,
+) -> Result<String, String> {
+  let mut result = String::new();
+  let mut chars = template.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '{' if chars.peek() == Some(&'{') => {
+        chars.next();
+        result.push('{');
+      }
+      '{' => {
+        let mut field = String::new();
+        loop {
+          match chars.next() {
+            Some('}') => break,
+            Some(c) => field.push(c),
+            None => return Err(String::from("'{'가 닫히지 않았습니다.")),
+          }
+        }
+
+        let (key, spec) = match field.find(':') {
+          Some(index) => (&field[..index], parse_spec(&field[index + 1..])?),
+          None => (&field[..], parse_spec("")?),
+        };
+        result.push_str(&render(&lookup(key.trim())?, &spec));
+      }
+      '}' if chars.peek() == Some(&'}') => {
+        chars.next();
+        result.push('}');
+      }
+      '}' => return Err(String::from("짝이 없는 '}'가 있습니다.")),
+      c => result.push(c),
+    }
+  }
+
+  Ok(result)
+}
+
+fn template_of(function: &'static str, value: &Constant) -> Result<String, HaneulError> {
+  match value {
+    Constant::Str(template) => Ok(template.to_string()),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+/// 빈 자리 `{}`는 차례대로, `{1}`처럼 번호를 적은 자리는 그 번호의 값으로 채웁니다.
+fn format_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let template = template_of("형식", &args[0])?;
+  let values = match &args[1] {
+    Constant::List(values) => values.clone(),
+    value => return Err(invalid_argument("형식", value)),
+  };
+
+  let mut next = 0;
+  let result = fill_template(&template, |key| {
+    let index = if key.is_empty() {
+      next += 1;
+      next - 1
+    } else {
+      key
+        .parse()
+        .map_err(|_| format!("'{}'은(는) 자리 번호가 아닙니다.", key))?
+    };
+    values
+      .get(index)
+      .cloned()
+      .ok_or_else(|| format!("{}번 값이 없습니다.", index))
+  });
+
+  result
+    .map(|text| Constant::Str(text.into()))
+    .map_err(|reason| HaneulError::InvalidFormat { template, reason })
+}
+
+/// 사전은 `[이름, 값]` 목록들의 목록입니다.
+fn dictionary_of(value: &Constant) -> Option<Vec<(String, Constant)>> {
+  match value {
+    Constant::List(entries) => entries
+      .iter()
+      .map(|entry| match entry {
+        Constant::List(pair) => match &pair[..] {
+          [Constant::Str(name), value] => Some((name.to_string(), value.clone())),
+          _ => None,
+        },
+        _ => None,
+      })
+      .collect(),
+    _ => None,
+  }
+}
+
+/// `{이름}`처럼 이름을 적은 자리를 사전에서 찾은 값으로 채웁니다.
+fn format_named_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let template = template_of("이름_형식", &args[0])?;
+  let dictionary =
+    dictionary_of(&args[1]).ok_or_else(|| invalid_argument("이름_형식", &args[1]))?;
+
+  let result = fill_template(&template, |key| {
+    dictionary
+      .iter()
+      .find(|(name, _)| name == key)
+      .map(|(_, value)| value.clone())
+      .ok_or_else(|| format!("'{}'이라는 이름의 값이 없습니다.", key))
+  });
+
+  result
+    .map(|text| Constant::Str(text.into()))
+    .map_err(|reason| HaneulError::InvalidFormat { template, reason })
+}
+
+pub(super) fn functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "형식", vec!["을"], true, format_func),
+    make_native(
+      josa_table,
+      "이름_형식",
+      vec!["을", "로"],
+      false,
+      format_named_func,
+    ),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use crate::builtin::test_util::{call, text};
+
+  fn list(values: Vec<Constant>) -> Constant {
+    Constant::List(Rc::new(values))
+  }
+
+  fn format(template: &str, values: Vec<Constant>) -> Result<Constant, HaneulError> {
+    call("형식", vec![text(template), list(values)])
+  }
+
+  #[test]
+  fn fill_positional_placeholders() {
+    use Constant::{Boolean, Integer, Real};
+
+    assert_eq!(
+      format("{}와 {}", vec![text("하늘"), Boolean(true)]).unwrap(),
+      text("하늘와 참")
+    );
+    assert_eq!(
+      format("{1}-{0}-{1}", vec![Integer(1), Integer(2)]).unwrap(),
+      text("2-1-2")
+    );
+    assert_eq!(
+      format(
+        "[{:5}][{:<5}][{:^6}]",
+        vec![Integer(42), Integer(42), text("가나")]
+      )
+      .unwrap(),
+      text("[   42][42   ][  가나  ]")
+    );
+    assert_eq!(
+      format(
+        "{:*>6.2}|{:05}|{:08.3}",
+        vec![Real(1.23456), Integer(-42), Real(-2.5)]
+      )
+      .unwrap(),
+      text("**1.23|-0042|-002.500")
+    );
+    assert_eq!(
+      format("{:.2}원, {:.1}", vec![Integer(5), text("하늘")]).unwrap(),
+      text("5.00원, 하")
+    );
+    assert_eq!(
+      format(
+        "{:.2}|{:.0}",
+        vec![Integer(9007199254740993), Integer(i64::MIN)]
+      )
+      .unwrap(),
+      text("9007199254740993.00|-9223372036854775808")
+    );
+    assert_eq!(
+      format("{{{}}}", vec![list(vec![Integer(1), Real(2.0)])]).unwrap(),
+      text("{[1, 2.0]}")
+    );
+  }
+
+  #[test]
+  fn fill_named_placeholders() {
+    let dictionary = list(vec![
+      list(vec![text("이름"), text("하늘")]),
+      list(vec![text("점수"), Constant::Real(91.25)]),
+    ]);
+    assert_eq!(
+      call(
+        "이름_형식",
+        vec![text("{이름}: {점수:>7.1}점"), dictionary.clone()]
+      )
+      .unwrap(),
+      text("하늘:    91.2점")
+    );
+
+    assert!(matches!(
+      call("이름_형식", vec![text("{나이}"), dictionary]),
+      Err(HaneulError::InvalidFormat { reason, .. }) if reason.contains("나이")
+    ));
+    assert!(matches!(
+      call("이름_형식", vec![text("{}"), list(vec![text("이름")])]),
+      Err(HaneulError::InvalidArgument {
+        function: "이름_형식",
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn report_format_errors() {
+    for template in &["{", "}", "{:x}", "{:.}", "{2}", "{이름}"] {
+      assert!(
+        matches!(
+          format(template, vec![Constant::Integer(1)]),
+          Err(HaneulError::InvalidFormat { .. })
+        ),
+        "{}",
+        template
+      );
+    }
+    for template in &[
+      "{:18446744073709551615}",
+      "{:9999999999}",
+      "{:1001}",
+      "{:.1001}",
+      "{:1000000000000000000000}",
+    ] {
+      assert!(
+        matches!(
+          format(template, vec![Constant::Integer(1)]),
+          Err(HaneulError::InvalidFormat { reason, .. }) if reason.contains("1000")
+        ),
+        "{}",
+        template
+      );
+    }
+    assert_eq!(
+      format("{:1000}", vec![text("")]).unwrap(),
+      text(&" ".repeat(1000))
+    );
+
+    assert!(matches!(
+      call("형식", vec![Constant::Integer(1), list(vec![])]),
+      Err(HaneulError::InvalidArgument {
+        function: "형식",
+        ..
+      })
+    ));
+  }
+}
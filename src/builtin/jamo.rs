@@ -0,0 +1,208 @@
+use std::rc::Rc;
+
+use super::{domain_error, invalid_argument, make_native};
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::hangul;
+use crate::io::Io;
+use crate::josa::JosaTable;
+
+/// 문자는 초성, 중성, 종성의 목록으로 나누고, 문자열은 자모를 풀어 쓴 문자열로 바꿉니다.
+fn decompose_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match &args[0] {
+    Constant::Char(c) => {
+      let (cho, jung, jong) =
+        hangul::decompose(*c).ok_or_else(|| domain_error("자모_분해", &args[0]))?;
+      let jamo = vec![cho, jung].into_iter().chain(jong).map(Constant::Char);
+      Ok(Constant::List(Rc::new(jamo.collect())))
+    }
+    Constant::Str(text) => {
+      let jamo: String = hangul::to_jamo(text).into_iter().collect();
+      Ok(Constant::Str(jamo.into()))
+    }
+    value => Err(invalid_argument("자모_분해", value)),
+  }
+}
+
+/// 자모 두세 개가 든 목록이나 문자열을 음절 하나로 합칩니다.
+fn compose_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let jamo: Vec<char> = match &args[0] {
+    Constant::List(list) => list
+      .iter()
+      .map(|value| match value {
+        Constant::Char(c) => Ok(*c),
+        _ => Err(invalid_argument("자모_조합", &args[0])),
+      })
+      .collect::<Result<_, _>>()?,
+    Constant::Str(text) => text.chars().collect(),
+    value => return Err(invalid_argument("자모_조합", value)),
+  };
+
+  let syllable = match jamo[..] {
+    [cho, jung] => hangul::compose(cho, jung, None),
+    [cho, jung, jong] => hangul::compose(cho, jung, Some(jong)),
+    _ => None,
+  };
+  syllable
+    .map(Constant::Char)
+    .ok_or_else(|| domain_error("자모_조합", &args[0]))
+}
+
+/// 문자열은 마지막 글자에 받침이 있는지 봅니다.
+fn has_final_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let last = match &args[0] {
+    Constant::Char(c) => Some(*c),
+    Constant::Str(text) => text.chars().last(),
+    value => return Err(invalid_argument("받침_있음", value)),
+  };
+
+  last
+    .and_then(hangul::has_final_consonant)
+    .map(Constant::Boolean)
+    .ok_or_else(|| domain_error("받침_있음", &args[0]))
+}
+
+fn initials_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match &args[0] {
+    Constant::Char(c) => Ok(Constant::Char(
+      hangul::decompose(*c).map_or(*c, |(cho, _, _)| cho),
+    )),
+    Constant::Str(text) => Ok(Constant::Str(hangul::initials(text).into())),
+    value => Err(invalid_argument("초성", value)),
+  }
+}
+
+/// 단어 뒤에 조사를 붙인 문자열을 돌려줍니다.
+fn attach_josa_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match (&args[0], &args[1]) {
+    (Constant::Str(word), Constant::Str(josa)) => hangul::attach_josa(word, josa)
+      .map(|text| Constant::Str(text.into()))
+      .ok_or_else(|| domain_error("조사_붙이기", &args[1])),
+    (Constant::Str(_), value) | (value, _) => Err(invalid_argument("조사_붙이기", value)),
+  }
+}
+
+pub(super) fn functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "자모_분해", vec!["을"], false, decompose_func),
+    make_native(josa_table, "자모_조합", vec!["을"], false, compose_func),
+    make_native(josa_table, "받침_있음", vec!["을"], false, has_final_func),
+    make_native(josa_table, "초성", vec!["을"], false, initials_func),
+    make_native(
+      josa_table,
+      "조사_붙이기",
+      vec!["에", "을"],
+      false,
+      attach_josa_func,
+    ),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::builtin::test_util::{call, text};
+
+  fn chars(value: &str) -> Constant {
+    Constant::List(Rc::new(value.chars().map(Constant::Char).collect()))
+  }
+
+  #[test]
+  fn handle_jamo() {
+    use Constant::{Boolean, Char};
+
+    assert_eq!(
+      call("자모_분해", vec![Char('닭')]).unwrap(),
+      chars("ㄷㅏㄺ")
+    );
+    assert_eq!(call("자모_분해", vec![Char('가')]).unwrap(), chars("ㄱㅏ"));
+    assert_eq!(
+      call("자모_분해", vec![text("값 1")]).unwrap(),
+      text("ㄱㅏㅄ 1")
+    );
+    assert_eq!(
+      call("자모_조합", vec![chars("ㄷㅏㄺ")]).unwrap(),
+      Char('닭')
+    );
+    assert_eq!(call("자모_조합", vec![text("ㅎㅢ")]).unwrap(), Char('희'));
+
+    assert_eq!(call("받침_있음", vec![Char('힣')]).unwrap(), Boolean(true));
+    assert_eq!(
+      call("받침_있음", vec![text("하늘")]).unwrap(),
+      Boolean(true)
+    );
+    assert_eq!(
+      call("받침_있음", vec![text("바다")]).unwrap(),
+      Boolean(false)
+    );
+
+    assert_eq!(
+      call("초성", vec![text("하늘 언어!")]).unwrap(),
+      text("ㅎㄴ ㅇㅇ!")
+    );
+    assert_eq!(call("초성", vec![Char('뷁')]).unwrap(), Char('ㅂ'));
+    assert_eq!(call("초성", vec![Char('a')]).unwrap(), Char('a'));
+  }
+
+  #[test]
+  fn attach_josa() {
+    let attach = |word: &str, josa: &str| call("조사_붙이기", vec![text(word), text(josa)]);
+
+    assert_eq!(attach("사과", "을/를").unwrap(), text("사과를"));
+    assert_eq!(attach("서울", "으로").unwrap(), text("서울로"));
+    assert_eq!(attach("7", "이").unwrap(), text("7이"));
+    assert!(matches!(
+      attach("사과", "에게"),
+      Err(HaneulError::DomainError {
+        function: "조사_붙이기",
+        ..
+      })
+    ));
+
+    assert!(matches!(
+      call("조사_붙이기", vec![Constant::Integer(3), text("을")]),
+      Err(HaneulError::InvalidArgument { .. })
+    ));
+  }
+
+  #[test]
+  fn report_jamo_errors() {
+    use Constant::{Char, Integer};
+
+    assert!(matches!(
+      call("자모_분해", vec![Char('ㄱ')]),
+      Err(HaneulError::DomainError {
+        function: "자모_분해",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("자모_조합", vec![text("ㄱㄴㄷ")]),
+      Err(HaneulError::DomainError { .. })
+    ));
+    assert!(matches!(
+      call("자모_조합", vec![text("ㄱㅏㄴㄷ")]),
+      Err(HaneulError::DomainError { .. })
+    ));
+    assert!(matches!(
+      call("자모_조합", vec![Constant::List(Rc::new(vec![Integer(1)]))]),
+      Err(HaneulError::InvalidArgument {
+        function: "자모_조합",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("받침_있음", vec![text("")]),
+      Err(HaneulError::DomainError { .. })
+    ));
+    assert!(matches!(
+      call("받침_있음", vec![text("abc")]),
+      Err(HaneulError::DomainError { .. })
+    ));
+    assert!(matches!(
+      call("초성", vec![Integer(1)]),
+      Err(HaneulError::InvalidArgument { .. })
+    ));
+  }
+}
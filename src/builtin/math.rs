@@ -0,0 +1,350 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use super::{domain_error, invalid_argument, make_native, real_to_integer};
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::io::Io;
+use crate::josa::JosaTable;
+
+/// 정수와 실수를 모두 받는 함수에서 인수를 실수로 바꿉니다.
+fn to_real(function: &'static str, value: &Constant) -> Result<f64, HaneulError> {
+  match value {
+    Constant::Integer(v) => Ok(*v as f64),
+    Constant::Real(v) => Ok(*v),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+fn to_integer(function: &'static str, value: &Constant) -> Result<i64, HaneulError> {
+  match value {
+    Constant::Integer(v) => Ok(*v),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+/// 실수 결과가 NaN이면 `value`가 정의역을 벗어난 것으로 봅니다.
+fn real_result(
+  function: &'static str,
+  result: f64,
+  value: &Constant,
+) -> Result<Constant, HaneulError> {
+  if result.is_nan() {
+    Err(domain_error(function, value))
+  } else {
+    Ok(Constant::Real(result))
+  }
+}
+
+fn abs_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match &args[0] {
+    Constant::Integer(v) => {
+      v.checked_abs()
+        .map(Constant::Integer)
+        .ok_or(HaneulError::IntegerOverflow {
+          function: "절댓값"
+        })
+    }
+    Constant::Real(v) => Ok(Constant::Real(v.abs())),
+    value => Err(invalid_argument("절댓값", value)),
+  }
+}
+
+/// 정수를 0 이상의 정수로 거듭제곱하면 정수, 그 밖에는 실수가 됩니다.
+fn pow_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  match (&args[0], &args[1]) {
+    (Constant::Integer(base), Constant::Integer(exp)) if *exp >= 0 => u32::try_from(*exp)
+      .ok()
+      .and_then(|exp| base.checked_pow(exp))
+      .map(Constant::Integer)
+      .ok_or(HaneulError::IntegerOverflow {
+        function: "거듭제곱",
+      }),
+    (base, exp) => {
+      let result = to_real("거듭제곱", base)?.powf(to_real("거듭제곱", exp)?);
+      real_result("거듭제곱", result, base)
+    }
+  }
+}
+
+fn sqrt_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let value = to_real("제곱근", &args[0])?;
+  real_result("제곱근", value.sqrt(), &args[0])
+}
+
+/// 실수를 정수로 바꾸는 함수들은 결과도 정수입니다. 정수는 그대로 돌려줍니다.
+fn round_with(
+  function: &'static str,
+  value: &Constant,
+  round: fn(f64) -> f64,
+) -> Result<Constant, HaneulError> {
+  match value {
+    Constant::Integer(v) => Ok(Constant::Integer(*v)),
+    Constant::Real(v) => real_to_integer(function, round(*v), value),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+fn floor_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  round_with("내림", &args[0], f64::floor)
+}
+
+fn ceil_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  round_with("올림", &args[0], f64::ceil)
+}
+
+fn round_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  round_with("반올림", &args[0], f64::round)
+}
+
+macro_rules! real_function {
+  ($fn_name: ident, $name: expr, $op: expr) => {
+    fn $fn_name(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+      let op: fn(f64) -> f64 = $op;
+      real_result($name, op(to_real($name, &args[0])?), &args[0])
+    }
+  };
+}
+
+real_function!(sin_func, "사인", f64::sin);
+real_function!(cos_func, "코사인", f64::cos);
+real_function!(tan_func, "탄젠트", f64::tan);
+real_function!(exp_func, "지수", f64::exp);
+real_function!(ln_func, "로그", ln_positive);
+
+/// 0의 로그는 음의 무한대지만, 정의역을 벗어난 것으로 다룹니다.
+fn ln_positive(x: f64) -> f64 {
+  if x > 0.0 {
+    x.ln()
+  } else {
+    f64::NAN
+  }
+}
+
+/// 두 값을 비교합니다. 둘 다 정수면 그대로 비교하고, 실수가 섞여 있을 때만 실수로 바꿔 비교합니다.
+fn compare(
+  function: &'static str,
+  lhs: &Constant,
+  rhs: &Constant,
+) -> Result<Option<Ordering>, HaneulError> {
+  match (lhs, rhs) {
+    (Constant::Integer(lhs), Constant::Integer(rhs)) => Ok(Some(lhs.cmp(rhs))),
+    (lhs, rhs) => Ok(to_real(function, lhs)?.partial_cmp(&to_real(function, rhs)?)),
+  }
+}
+
+/// 가장 앞에 있는 값을 남기며 `wanted` 쪽으로 비교하여 고릅니다. 정수와 실수는 섞어서 비교합니다.
+fn select(
+  function: &'static str,
+  args: Vec<Constant>,
+  wanted: Ordering,
+) -> Result<Constant, HaneulError> {
+  let mut values = vec![args[0].clone()];
+  if let Constant::List(rest) = &args[1] {
+    values.extend(rest.iter().cloned());
+  }
+
+  let mut selected = values[0].clone();
+  // 값이 하나뿐이어도 숫자인지 확인합니다.
+  to_real(function, &selected)?;
+  for value in values.into_iter().skip(1) {
+    if compare(function, &value, &selected)? == Some(wanted) {
+      selected = value;
+    }
+  }
+
+  Ok(selected)
+}
+
+fn max_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  select("최댓값", args, Ordering::Greater)
+}
+
+fn min_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  select("최솟값", args, Ordering::Less)
+}
+
+fn gcd(a: i64, b: i64) -> u64 {
+  let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+  while b != 0 {
+    let r = a % b;
+    a = b;
+    b = r;
+  }
+  a
+}
+
+fn gcd_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let a = to_integer("최대공약수", &args[0])?;
+  let b = to_integer("최대공약수", &args[1])?;
+  i64::try_from(gcd(a, b))
+    .map(Constant::Integer)
+    .map_err(|_| HaneulError::IntegerOverflow {
+      function: "최대공약수",
+    })
+}
+
+fn lcm_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let a = to_integer("최소공배수", &args[0])?;
+  let b = to_integer("최소공배수", &args[1])?;
+  if a == 0 || b == 0 {
+    return Ok(Constant::Integer(0));
+  }
+
+  (a.unsigned_abs() / gcd(a, b))
+    .checked_mul(b.unsigned_abs())
+    .and_then(|x| i64::try_from(x).ok())
+    .map(Constant::Integer)
+    .ok_or(HaneulError::IntegerOverflow {
+      function: "최소공배수",
+    })
+}
+
+pub(super) fn functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "절댓값", vec!["을"], false, abs_func),
+    make_native(josa_table, "거듭제곱", vec!["을", "로"], false, pow_func),
+    make_native(josa_table, "제곱근", vec!["을"], false, sqrt_func),
+    make_native(josa_table, "내림", vec!["을"], false, floor_func),
+    make_native(josa_table, "올림", vec!["을"], false, ceil_func),
+    make_native(josa_table, "반올림", vec!["을"], false, round_func),
+    make_native(josa_table, "사인", vec!["을"], false, sin_func),
+    make_native(josa_table, "코사인", vec!["을"], false, cos_func),
+    make_native(josa_table, "탄젠트", vec!["을"], false, tan_func),
+    make_native(josa_table, "지수", vec!["을"], false, exp_func),
+    make_native(josa_table, "로그", vec!["을"], false, ln_func),
+    make_native(josa_table, "최댓값", vec!["을"], true, max_func),
+    make_native(josa_table, "최솟값", vec!["을"], true, min_func),
+    make_native(josa_table, "최대공약수", vec!["을", "와"], false, gcd_func),
+    make_native(josa_table, "최소공배수", vec!["을", "와"], false, lcm_func),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use crate::builtin::test_util::call;
+
+  #[test]
+  fn mix_integer_and_real() {
+    use Constant::{Integer, Real};
+
+    assert_eq!(call("절댓값", vec![Integer(-3)]).unwrap(), Integer(3));
+    assert_eq!(call("절댓값", vec![Real(-2.5)]).unwrap(), Real(2.5));
+    assert_eq!(
+      call("거듭제곱", vec![Integer(2), Integer(10)]).unwrap(),
+      Integer(1024)
+    );
+    assert_eq!(
+      call("거듭제곱", vec![Integer(2), Integer(-1)]).unwrap(),
+      Real(0.5)
+    );
+    assert_eq!(
+      call("거듭제곱", vec![Real(4.0), Real(0.5)]).unwrap(),
+      Real(2.0)
+    );
+    assert_eq!(call("제곱근", vec![Integer(9)]).unwrap(), Real(3.0));
+    assert_eq!(call("내림", vec![Real(-1.5)]).unwrap(), Integer(-2));
+    assert_eq!(call("올림", vec![Real(1.2)]).unwrap(), Integer(2));
+    assert_eq!(call("반올림", vec![Real(2.5)]).unwrap(), Integer(3));
+    assert_eq!(call("반올림", vec![Integer(7)]).unwrap(), Integer(7));
+    assert_eq!(call("사인", vec![Integer(0)]).unwrap(), Real(0.0));
+    assert_eq!(call("로그", vec![Integer(1)]).unwrap(), Real(0.0));
+
+    let rest = |values: Vec<Constant>| Constant::List(Rc::new(values));
+    assert_eq!(
+      call(
+        "최댓값",
+        vec![Integer(3), rest(vec![Real(3.5), Integer(2)])]
+      )
+      .unwrap(),
+      Real(3.5)
+    );
+    assert_eq!(
+      call(
+        "최솟값",
+        vec![Integer(3), rest(vec![Real(3.0), Integer(4)])]
+      )
+      .unwrap(),
+      Integer(3)
+    );
+    assert_eq!(
+      call(
+        "최댓값",
+        vec![Integer(i64::MAX - 1), rest(vec![Integer(i64::MAX)])]
+      )
+      .unwrap(),
+      Integer(i64::MAX)
+    );
+    assert_eq!(
+      call(
+        "최솟값",
+        vec![Integer(i64::MIN + 1), rest(vec![Integer(i64::MIN)])]
+      )
+      .unwrap(),
+      Integer(i64::MIN)
+    );
+    assert_eq!(
+      call("최대공약수", vec![Integer(-12), Integer(18)]).unwrap(),
+      Integer(6)
+    );
+    assert_eq!(
+      call("최소공배수", vec![Integer(4), Integer(6)]).unwrap(),
+      Integer(12)
+    );
+  }
+
+  #[test]
+  fn report_math_errors() {
+    use Constant::{Boolean, Integer, Real};
+
+    assert!(matches!(
+      call("제곱근", vec![Integer(-1)]),
+      Err(HaneulError::DomainError {
+        function: "제곱근",
+        value: Integer(-1)
+      })
+    ));
+    assert!(matches!(
+      call("로그", vec![Real(0.0)]),
+      Err(HaneulError::DomainError {
+        function: "로그",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("거듭제곱", vec![Real(-8.0), Real(0.5)]),
+      Err(HaneulError::DomainError { .. })
+    ));
+    assert!(matches!(
+      call("거듭제곱", vec![Integer(2), Integer(64)]),
+      Err(HaneulError::IntegerOverflow {
+        function: "거듭제곱"
+      })
+    ));
+    assert!(matches!(
+      call("절댓값", vec![Integer(i64::MIN)]),
+      Err(HaneulError::IntegerOverflow { .. })
+    ));
+    assert!(matches!(
+      call("반올림", vec![Real(1e20)]),
+      Err(HaneulError::IntegerOverflow {
+        function: "반올림"
+      })
+    ));
+    assert!(matches!(
+      call("최대공약수", vec![Real(1.0), Integer(2)]),
+      Err(HaneulError::InvalidArgument {
+        function: "최대공약수",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("사인", vec![Boolean(true)]),
+      Err(HaneulError::InvalidArgument { .. })
+    ));
+  }
+}
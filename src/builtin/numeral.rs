@@ -0,0 +1,338 @@
+use std::convert::TryFrom;
+
+use super::{domain_error, invalid_argument, make_native};
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::io::Io;
+use crate::josa::JosaTable;
+
+const DIGITS: [char; 10] = ['영', '일', '이', '삼', '사', '오', '육', '칠', '팔', '구'];
+const SMALL_UNITS: [(char, i128); 3] = [('천', 1000), ('백', 100), ('십', 10)];
+const BIG_UNITS: [(&str, i128); 5] = [
+  ("", 1),
+  ("만", 10_000),
+  ("억", 100_000_000),
+  ("조", 1_000_000_000_000),
+  ("경", 10_000_000_000_000_000),
+];
+
+const NATIVE_TENS: [&str; 10] = [
+  "", "열", "스물", "서른", "마흔", "쉰", "예순", "일흔", "여든", "아흔",
+];
+const NATIVE_ONES: [&str; 10] = [
+  "", "하나", "둘", "셋", "넷", "다섯", "여섯", "일곱", "여덟", "아홉",
+];
+
+/// 0보다 큰 값을 네 자리씩 끊어 큰 자리부터 `(묶음, 단위)`로 돌려줍니다. 0인 묶음은 뺍니다.
+fn groups(value: u64) -> Vec<(u64, &'static str)> {
+  let mut result = Vec::new();
+  let mut rest = value;
+  for (unit, _) in BIG_UNITS.iter() {
+    if !rest.is_multiple_of(10_000) {
+      result.push((rest % 10_000, *unit));
+    }
+    rest /= 10_000;
+  }
+  result.reverse();
+  result
+}
+
+/// 네 자리 묶음을 읽습니다. 십, 백, 천 앞의 일은 읽지 않습니다.
+fn sino_group(group: u64) -> String {
+  let mut text = String::new();
+  for (unit, size) in SMALL_UNITS.iter() {
+    let digit = (group / *size as u64 % 10) as usize;
+    if digit > 1 {
+      text.push(DIGITS[digit]);
+    }
+    if digit > 0 {
+      text.push(*unit);
+    }
+  }
+  if !group.is_multiple_of(10) {
+    text.push(DIGITS[(group % 10) as usize]);
+  }
+  text
+}
+
+/// 만 단위로 띄어 쓴 한자어 수입니다. `10000`은 일만이 아닌 만으로 읽습니다.
+fn to_sino_korean(value: i64) -> String {
+  if value == 0 {
+    return String::from("영");
+  }
+
+  let words: Vec<String> = groups(value.unsigned_abs())
+    .into_iter()
+    .map(|(group, unit)| match (group, unit) {
+      (1, "만") => String::from("만"),
+      (1, unit) if !unit.is_empty() => format!("일{}", unit),
+      (group, unit) => format!("{}{}", sino_group(group), unit),
+    })
+    .collect();
+  let sign = if value < 0 { "마이너스 " } else { "" };
+  format!("{}{}", sign, words.join(" "))
+}
+
+/// 네 자리씩 아라비아 숫자로 적고 만, 억, 조, 경을 붙입니다.
+fn to_grouped(value: i64) -> String {
+  if value == 0 {
+    return String::from("0");
+  }
+
+  let words: Vec<String> = groups(value.unsigned_abs())
+    .into_iter()
+    .map(|(group, unit)| format!("{}{}", group, unit))
+    .collect();
+  let sign = if value < 0 { "-" } else { "" };
+  format!("{}{}", sign, words.join(" "))
+}
+
+/// 1부터 99까지를 고유어 수사로 읽습니다.
+fn to_native_korean(value: i64) -> Option<String> {
+  if !(1..=99).contains(&value) {
+    return None;
+  }
+
+  let value = value as usize;
+  Some(format!(
+    "{}{}",
+    NATIVE_TENS[value / 10],
+    NATIVE_ONES[value % 10]
+  ))
+}
+
+/// 한자어 수, 아라비아 숫자와 섞어 쓴 만 단위 수, 고유어 수사를 읽습니다.
+/// 읽을 수 없으면 `None`이고, 너무 큰 수는 `i64`의 범위를 넘는 `i128`로 돌려줍니다.
+fn parse_korean(text: &str) -> Option<i128> {
+  let text: String = text
+    .chars()
+    .filter(|c| !c.is_whitespace() && *c != ',')
+    .collect();
+  if let Some(value) = (1..=99).find(|n| to_native_korean(*n).as_deref() == Some(&text)) {
+    return Some(value as i128);
+  }
+
+  let (negative, text) = if let Some(rest) = text.strip_prefix("마이너스") {
+    (true, rest)
+  } else if let Some(rest) = text.strip_prefix('-') {
+    (true, rest)
+  } else {
+    (false, &text[..])
+  };
+  if text.is_empty() {
+    return None;
+  }
+  if text == "영" {
+    return Some(0);
+  }
+
+  let mut total: i128 = 0;
+  // 만 아래의 묶음과 아직 단위를 만나지 않은 숫자입니다.
+  let mut section: i128 = 0;
+  let mut number: Option<i128> = None;
+  let mut arabic = false;
+  let mut last_small = i128::MAX;
+  let mut last_big = i128::MAX;
+
+  for c in text.chars() {
+    if let Some(digit) = c.to_digit(10) {
+      if number.is_some() && !arabic {
+        return None;
+      }
+      number = Some(number.unwrap_or(0).checked_mul(10)? + digit as i128);
+      arabic = true;
+    } else if let Some(digit) = DIGITS.iter().skip(1).position(|x| *x == c) {
+      if number.is_some() {
+        return None;
+      }
+      number = Some(digit as i128 + 1);
+      arabic = false;
+    } else if let Some(&(_, size)) = SMALL_UNITS.iter().find(|(unit, _)| *unit == c) {
+      if size >= last_small || number.is_some_and(|n| n >= 10) {
+        return None;
+      }
+      section += number.take().unwrap_or(1) * size;
+      last_small = size;
+    } else if let Some(&(_, size)) = BIG_UNITS
+      .iter()
+      .skip(1)
+      .find(|(unit, _)| unit.starts_with(c))
+    {
+      let group = section + number.take().unwrap_or(0);
+      if size >= last_big || group >= 10_000 {
+        return None;
+      }
+      total += if group == 0 { 1 } else { group } * size;
+      section = 0;
+      last_small = i128::MAX;
+      last_big = size;
+    } else {
+      return None;
+    }
+  }
+
+  let group = section + number.unwrap_or(0);
+  if last_big != i128::MAX && group >= 10_000 {
+    return None;
+  }
+  let value = total + group;
+  Some(if negative { -value } else { value })
+}
+
+fn to_integer(function: &'static str, value: &Constant) -> Result<i64, HaneulError> {
+  match value {
+    Constant::Integer(v) => Ok(*v),
+    _ => Err(invalid_argument(function, value)),
+  }
+}
+
+fn sino_korean_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let value = to_integer("한자어_수", &args[0])?;
+  Ok(Constant::Str(to_sino_korean(value).into()))
+}
+
+fn native_korean_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let value = to_integer("고유어_수", &args[0])?;
+  to_native_korean(value)
+    .map(|text| Constant::Str(text.into()))
+    .ok_or_else(|| domain_error("고유어_수", &args[0]))
+}
+
+fn grouped_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let value = to_integer("만_단위", &args[0])?;
+  Ok(Constant::Str(to_grouped(value).into()))
+}
+
+fn parse_korean_func(_: &mut Io, args: Vec<Constant>) -> Result<Constant, HaneulError> {
+  let text = match &args[0] {
+    Constant::Str(text) => text,
+    value => return Err(invalid_argument("한글_수_읽기", value)),
+  };
+
+  let value = parse_korean(text).ok_or_else(|| HaneulError::ParseFailed {
+    expected: "한글로 적은 수",
+    text: text.to_string(),
+  })?;
+  i64::try_from(value)
+    .map(Constant::Integer)
+    .map_err(|_| HaneulError::IntegerOverflow {
+      function: "한글_수_읽기",
+    })
+}
+
+pub(super) fn functions(josa_table: &mut JosaTable) -> Vec<Constant> {
+  vec![
+    make_native(josa_table, "한자어_수", vec!["을"], false, sino_korean_func),
+    make_native(
+      josa_table,
+      "고유어_수",
+      vec!["을"],
+      false,
+      native_korean_func,
+    ),
+    make_native(josa_table, "만_단위", vec!["을"], false, grouped_func),
+    make_native(
+      josa_table,
+      "한글_수_읽기",
+      vec!["을"],
+      false,
+      parse_korean_func,
+    ),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::builtin::test_util::{call, text};
+
+  #[test]
+  fn format_numerals() {
+    assert_eq!(to_sino_korean(1234), "천이백삼십사");
+    assert_eq!(to_sino_korean(10), "십");
+    assert_eq!(to_sino_korean(10_001), "만 일");
+    assert_eq!(
+      to_sino_korean(123_456_789),
+      "일억 이천삼백사십오만 육천칠백팔십구"
+    );
+    assert_eq!(to_sino_korean(-305), "마이너스 삼백오");
+    assert_eq!(
+      to_sino_korean(i64::MIN),
+      "마이너스 구백이십이경 삼천삼백칠십이조 삼백육십팔억 오천사백칠십칠만 오천팔백팔"
+    );
+
+    assert_eq!(to_grouped(123_450_000), "1억 2345만");
+    assert_eq!(to_grouped(100_000_005), "1억 5");
+    assert_eq!(to_grouped(-10_000), "-1만");
+
+    assert_eq!(to_native_korean(1).unwrap(), "하나");
+    assert_eq!(to_native_korean(20).unwrap(), "스물");
+    assert_eq!(to_native_korean(99).unwrap(), "아흔아홉");
+    assert_eq!(to_native_korean(0), None);
+  }
+
+  #[test]
+  fn parse_numerals() {
+    for value in &[
+      0,
+      7,
+      10,
+      1234,
+      10_001,
+      123_456_789,
+      -305,
+      i64::MAX,
+      i64::MIN,
+    ] {
+      assert_eq!(parse_korean(&to_sino_korean(*value)), Some(*value as i128));
+      assert_eq!(parse_korean(&to_grouped(*value)), Some(*value as i128));
+    }
+    assert_eq!(parse_korean("서른셋"), Some(33));
+    assert_eq!(parse_korean("일천이백"), Some(1200));
+    assert_eq!(parse_korean("3천5백"), Some(3500));
+    assert_eq!(parse_korean("1,234"), Some(1234));
+
+    for invalid in &[
+      "",
+      "십백",
+      "이삼",
+      "12345만",
+      "만억",
+      "일 2",
+      "1억 12345",
+      "하나둘",
+    ] {
+      assert_eq!(parse_korean(invalid), None, "{}", invalid);
+    }
+  }
+
+  #[test]
+  fn report_numeral_errors() {
+    assert_eq!(
+      call("한글_수_읽기", vec![text("일억 이천만")]).unwrap(),
+      Constant::Integer(120_000_000)
+    );
+    assert!(matches!(
+      call("고유어_수", vec![Constant::Integer(100)]),
+      Err(HaneulError::DomainError {
+        function: "고유어_수",
+        ..
+      })
+    ));
+    assert!(matches!(
+      call("한글_수_읽기", vec![text("천경")]),
+      Err(HaneulError::IntegerOverflow {
+        function: "한글_수_읽기"
+      })
+    ));
+    assert!(matches!(
+      call("한글_수_읽기", vec![text("사과")]),
+      Err(HaneulError::ParseFailed { text, .. }) if text == "사과"
+    ));
+    assert!(matches!(
+      call("한자어_수", vec![Constant::Real(1.5)]),
+      Err(HaneulError::InvalidArgument { .. })
+    ));
+  }
+}
@@ -1,9 +1,12 @@
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::mem;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
-
-use indexmap::IndexMap;
+use std::rc::Rc;
 
 use crate::funcobject::FuncObject;
+use crate::josa::{JosaId, JosaMap};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Constant {
@@ -12,7 +15,14 @@ pub enum Constant {
   Real(f64),
   Char(char),
   Boolean(bool),
-  Function { josa_map: IndexMap<String, Option<Constant>>, func_object: FuncObject },
+  Str(Rc<str>),
+  List(Rc<Vec<Constant>>),
+  /// 조사 목록과 나머지 인수는 값을 복사할 때 함께 공유되고, 부분 적용으로 바뀔 때만 복사됩니다.
+  Function {
+    josa_map: Rc<JosaMap>,
+    rest_args: Option<Rc<Vec<Constant>>>,
+    func_object: FuncObject,
+  },
 }
 
 macro_rules! binary_op {
@@ -64,9 +74,98 @@ impl Constant {
       Constant::Real(_) => "실수",
       Constant::Char(_) => "문자",
       Constant::Boolean(_) => "부울",
+      Constant::Str(_) => "문자열",
+      Constant::List(_) => "목록",
       Constant::Function { .. } => "함수",
     }
   }
+
+  /// 값이 차지하는 메모리의 대략적인 크기(바이트)입니다. `Rc`로 공유하는 부분은 `counter`가 세기로 한 것만 셉니다.
+  pub fn approx_size(&self, counter: &mut SizeCounter) -> usize {
+    let base = mem::size_of::<Constant>();
+    match self {
+      Constant::Str(value) if counter.visit(value) => base + value.len(),
+      Constant::List(list) if counter.visit(list) => {
+        base + list.iter().map(|x| x.approx_size(counter)).sum::<usize>()
+      }
+      Constant::Function {
+        josa_map,
+        rest_args,
+        func_object,
+      } => {
+        let mut size = base;
+        if counter.visit(josa_map) {
+          for (_, value) in josa_map.iter() {
+            size += mem::size_of::<JosaId>() + value.as_ref().map_or(0, |x| x.approx_size(counter));
+          }
+        }
+        if let Some(rest_args) = rest_args {
+          if counter.visit(rest_args) {
+            size += rest_args
+              .iter()
+              .map(|x| x.approx_size(counter))
+              .sum::<usize>();
+          }
+        }
+
+        size + func_object.approx_size(counter)
+      }
+      _ => base,
+    }
+  }
+}
+
+/// 값들의 크기를 셀 때 이미 센 `Rc`를 기억해 두어, 여러 값이 공유하는 부분을 한 번만 셉니다.
+#[derive(Debug, Default)]
+pub struct SizeCounter {
+  seen: HashSet<*const u8>,
+  only_unique: bool,
+}
+
+impl SizeCounter {
+  pub fn new() -> SizeCounter {
+    Default::default()
+  }
+
+  /// 다른 곳에서 함께 가리키지 않는 `Rc`만 셉니다. 방금 만든 값이 새로 차지한 크기를 어림할 때 씁니다.
+  pub fn unique() -> SizeCounter {
+    SizeCounter {
+      only_unique: true,
+      ..Default::default()
+    }
+  }
+
+  /// `rc`가 가리키는 내용을 세어야 하면 `true`입니다.
+  pub fn visit<T: ?Sized>(&mut self, rc: &Rc<T>) -> bool {
+    if self.only_unique && Rc::strong_count(rc) > 1 {
+      return false;
+    }
+    self.seen.insert(Rc::as_ptr(rc) as *const u8)
+  }
+}
+
+/// 사람이 읽기 좋은 형태입니다. `문자열로`와 문자열 형식화가 이 형태를 씁니다.
+impl fmt::Display for Constant {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Constant::None => write!(f, "(없음)"),
+      Constant::Integer(value) => write!(f, "{}", value),
+      Constant::Real(value) => write!(f, "{:?}", value),
+      Constant::Char(value) => write!(f, "{}", value),
+      Constant::Boolean(true) => write!(f, "참"),
+      Constant::Boolean(false) => write!(f, "거짓"),
+      Constant::Str(value) => write!(f, "{}", value),
+      Constant::List(values) => {
+        let values: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+        write!(f, "[{}]", values.join(", "))
+      }
+      Constant::Function {
+        func_object: FuncObject::NativeFunc { name, .. },
+        ..
+      } => write!(f, "<내장 함수 {}>", name),
+      Constant::Function { .. } => write!(f, "<함수>"),
+    }
+  }
 }
 
 binary_op_arith!(Add, add, +);
@@ -78,7 +177,7 @@ binary_op!(Rem, rem,
   (Integer(lhs), Integer(rhs) => Integer(lhs % rhs))
 );
 
-unary_op!(Neg, neg, 
+unary_op!(Neg, neg,
   (Integer(v) => Integer(-v)),
   (Real(v) => Real(-v))
 );
@@ -89,6 +188,7 @@ impl PartialOrd for Constant {
       (Constant::Integer(v1), Constant::Integer(v2)) => PartialOrd::partial_cmp(&v1, &v2),
       (Constant::Real(v1), Constant::Real(v2)) => PartialOrd::partial_cmp(&v1, &v2),
       (Constant::Char(v1), Constant::Char(v2)) => PartialOrd::partial_cmp(&v1, &v2),
+      (Constant::Str(v1), Constant::Str(v2)) => PartialOrd::partial_cmp(&v1, &v2),
       _ => None,
     }
   }
@@ -0,0 +1,299 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use crate::constant::Constant;
+use crate::funcobject::FuncObject;
+use crate::instruction::Instruction;
+use crate::josa::JosaTable;
+use crate::machine::{function_label, TOP_LEVEL_LABEL};
+use crate::program::Program;
+
+#[derive(Debug, Clone)]
+pub struct FunctionCoverage {
+  pub name: String,
+  pub first_line: u32,
+  pub calls: u64,
+  /// 이 함수의 명령어가 있는 줄과 그 줄이 실행된 횟수입니다.
+  pub line_hits: BTreeMap<u32, u64>,
+}
+
+impl FunctionCoverage {
+  pub fn lines_found(&self) -> usize {
+    self.line_hits.len()
+  }
+
+  pub fn lines_hit(&self) -> usize {
+    self.line_hits.values().filter(|x| **x > 0).count()
+  }
+}
+
+/// 프로그램의 어떤 줄이 실행되었고 어떤 줄이 한 번도 실행되지 않았는지 기록합니다.
+/// 실행하기 전에 프로그램 전체를 훑어 두므로, 호출되지 않은 함수도 보고서에 나타납니다.
+#[derive(Debug, Default)]
+pub struct Coverage {
+  functions: Vec<FunctionCoverage>,
+  function_indices: HashMap<String, usize>,
+  active_calls: Vec<usize>,
+}
+
+impl Coverage {
+  pub fn new(program: &Program) -> Coverage {
+    let mut coverage = Coverage::default();
+    coverage.add_function(TOP_LEVEL_LABEL.to_string(), &program.code);
+    coverage.collect_functions(&program.const_table, &program.josa_table);
+    coverage
+  }
+
+  fn collect_functions(&mut self, const_table: &[Constant], josa_table: &JosaTable) {
+    for value in const_table {
+      if let Constant::Function {
+        josa_map,
+        rest_args,
+        func_object: FuncObject::CodeObject {
+          code, const_table, ..
+        },
+      } = value
+      {
+        let mut local_names: Vec<&str> = josa_map.keys().map(|x| josa_table.name(x)).collect();
+        if rest_args.is_some() {
+          local_names.push("나머지");
+        }
+
+        self.add_function(function_label(&local_names, code), code);
+        self.collect_functions(const_table, josa_table);
+      }
+    }
+  }
+
+  /// 이름이 같은 코드 객체는 하나의 함수로 합쳐서 셉니다.
+  fn add_function(&mut self, name: String, code: &[Instruction]) {
+    let index = match self.function_indices.get(&name) {
+      Some(index) => *index,
+      None => {
+        self.functions.push(FunctionCoverage {
+          name: name.clone(),
+          first_line: code.first().map_or(0, |x| x.line_number),
+          calls: 0,
+          line_hits: BTreeMap::new(),
+        });
+        self.function_indices.insert(name, self.functions.len() - 1);
+        self.functions.len() - 1
+      }
+    };
+
+    let line_hits = &mut self.functions[index].line_hits;
+    for inst in code {
+      line_hits.entry(inst.line_number).or_insert(0);
+    }
+  }
+
+  pub fn enter(&mut self, name: &str) {
+    // 프로그램을 훑을 때 보지 못한 함수는 기록하지 않지만, 호출 깊이는 맞춰 둡니다.
+    let index = self
+      .function_indices
+      .get(name)
+      .copied()
+      .unwrap_or(usize::MAX);
+    if let Some(function) = self.functions.get_mut(index) {
+      function.calls += 1;
+    }
+    self.active_calls.push(index);
+  }
+
+  pub fn exit(&mut self) {
+    self.active_calls.pop();
+  }
+
+  pub fn exit_all(&mut self) {
+    self.active_calls.clear();
+  }
+
+  pub fn record_line(&mut self, line_number: u32) {
+    let index = match self.active_calls.last() {
+      Some(index) => *index,
+      None => return,
+    };
+
+    if let Some(function) = self.functions.get_mut(index) {
+      if let Some(hits) = function.line_hits.get_mut(&line_number) {
+        *hits += 1;
+      }
+    }
+  }
+
+  pub fn functions(&self) -> &[FunctionCoverage] {
+    &self.functions
+  }
+
+  /// 모든 함수의 기록을 합친 줄별 실행 횟수입니다.
+  pub fn line_hits(&self) -> BTreeMap<u32, u64> {
+    let mut line_hits = BTreeMap::new();
+    for function in &self.functions {
+      for (line, hits) in &function.line_hits {
+        *line_hits.entry(*line).or_insert(0) += hits;
+      }
+    }
+    line_hits
+  }
+
+  /// lcov 트레이스 파일 형식으로 씁니다. `source_path`는 `SF` 항목에 들어갈 경로입니다.
+  pub fn write_lcov(&self, out: &mut dyn Write, source_path: &str) -> io::Result<()> {
+    writeln!(out, "TN:")?;
+    writeln!(out, "SF:{}", source_path)?;
+
+    // lcov는 함수 이름에 쉼표가 들어가는 것을 허용하지 않습니다.
+    let lcov_name = |name: &str| name.replace(", ", "·");
+    for function in &self.functions {
+      writeln!(
+        out,
+        "FN:{},{}",
+        function.first_line,
+        lcov_name(&function.name)
+      )?;
+    }
+    for function in &self.functions {
+      writeln!(out, "FNDA:{},{}", function.calls, lcov_name(&function.name))?;
+    }
+    writeln!(out, "FNF:{}", self.functions.len())?;
+    writeln!(
+      out,
+      "FNH:{}",
+      self.functions.iter().filter(|x| x.calls > 0).count()
+    )?;
+
+    let line_hits = self.line_hits();
+    for (line, hits) in &line_hits {
+      writeln!(out, "DA:{},{}", line, hits)?;
+    }
+    writeln!(out, "LF:{}", line_hits.len())?;
+    writeln!(out, "LH:{}", line_hits.values().filter(|x| **x > 0).count())?;
+    writeln!(out, "end_of_record")
+  }
+
+  /// 함수별 줄 커버리지와 한 번도 실행되지 않은 줄을 사람이 읽기 좋은 형태로 씁니다.
+  pub fn write_summary(&self, out: &mut dyn Write) -> io::Result<()> {
+    let percent = |hit: usize, found: usize| {
+      if found == 0 {
+        100.0
+      } else {
+        hit as f64 * 100.0 / found as f64
+      }
+    };
+
+    writeln!(out, "== 줄 커버리지 ==")?;
+    for function in &self.functions {
+      let (hit, found) = (function.lines_hit(), function.lines_found());
+      writeln!(
+        out,
+        "  {:>6.1}% {:>5}/{:<5} {}",
+        percent(hit, found),
+        hit,
+        found,
+        function.name
+      )?;
+    }
+
+    let line_hits = self.line_hits();
+    let hit = line_hits.values().filter(|x| **x > 0).count();
+    writeln!(
+      out,
+      "  {:>6.1}% {:>5}/{:<5} 전체",
+      percent(hit, line_hits.len()),
+      hit,
+      line_hits.len()
+    )?;
+
+    let missed: Vec<String> = line_hits
+      .iter()
+      .filter(|(_, hits)| **hits == 0)
+      .map(|(line, _)| line.to_string())
+      .collect();
+    if !missed.is_empty() {
+      writeln!(out, "  실행되지 않은 줄 : {}", missed.join(", "))?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use crate::josa::JosaMap;
+  use crate::machine::{Machine, StackFrame};
+  use crate::opcode::Opcode;
+
+  fn inst(line_number: u32, opcode: Opcode) -> Instruction {
+    Instruction {
+      line_number,
+      opcode,
+    }
+  }
+
+  #[test]
+  fn report_unreached_lines_and_functions() {
+    let mut josa_table = JosaTable::new();
+    let josa_list = vec![josa_table.intern("을"), josa_table.intern("에")];
+
+    let unused = Constant::Function {
+      josa_map: Rc::new(JosaMap::new(josa_list)),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![inst(5, Opcode::Load(0)), inst(6, Opcode::Load(1))]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+
+    let program = Program {
+      const_table: vec![unused, Constant::Boolean(false), Constant::Integer(1)],
+      code: vec![
+        inst(1, Opcode::Push(0)),
+        inst(1, Opcode::StoreGlobal(0)),
+        inst(2, Opcode::Push(1)),
+        inst(2, Opcode::PopJmpIfFalse(6)),
+        inst(3, Opcode::Push(2)),
+        inst(3, Opcode::Pop),
+      ],
+      global_var_names: vec![String::from("함수")],
+      josa_table,
+      imports: Vec::new(),
+      exports: Vec::new(),
+    };
+
+    let mut machine = Machine::new(
+      Vec::new(),
+      program.global_var_names.clone(),
+      program.josa_table.clone(),
+    );
+    machine.set_coverage(Some(Coverage::new(&program)));
+    assert!(machine
+      .run(StackFrame::new(
+        program.code.clone(),
+        program.const_table.clone()
+      ))
+      .is_ok());
+    let coverage = machine.take_coverage().unwrap();
+
+    let mut lcov = Vec::new();
+    coverage.write_lcov(&mut lcov, "시험.hnl").unwrap();
+    assert_eq!(
+      String::from_utf8(lcov).unwrap(),
+      "TN:\nSF:시험.hnl\n\
+       FN:1,(최상위)\nFN:5,함수(을·에) 5번째 줄\n\
+       FNDA:1,(최상위)\nFNDA:0,함수(을·에) 5번째 줄\nFNF:2\nFNH:1\n\
+       DA:1,2\nDA:2,2\nDA:3,0\nDA:5,0\nDA:6,0\nLF:5\nLH:2\nend_of_record\n"
+    );
+
+    let mut summary = Vec::new();
+    coverage.write_summary(&mut summary).unwrap();
+    let summary = String::from_utf8(summary).unwrap();
+    assert!(summary.contains("   66.7%     2/3     (최상위)"));
+    assert!(summary.contains("    0.0%     0/2     함수(을, 에) 5번째 줄"));
+    assert!(summary.ends_with("  실행되지 않은 줄 : 3, 5, 6\n"));
+  }
+}
@@ -0,0 +1,392 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::debugger::{run_until, StepMode, StopReason};
+use crate::io::MemoryOutput;
+use crate::machine::{Machine, StackFrame, TOP_LEVEL_LABEL};
+use crate::module::ModuleLoader;
+use crate::parser::program;
+
+const THREAD_ID: i64 = 1;
+
+/// 변수 참조 번호 하나에 프레임 번호와 범위 종류를 함께 담습니다.
+const SCOPE_KINDS: i64 = 3;
+const SCOPE_LOCALS: i64 = 1;
+const SCOPE_FREE_VARS: i64 = 2;
+const SCOPE_GLOBALS: i64 = 3;
+
+/// 표준 입출력으로 Debug Adapter Protocol 메시지를 주고받는 디버그 서버입니다.
+pub struct DapServer<R, W> {
+  input: R,
+  output: W,
+  seq: i64,
+  machine: Option<Machine>,
+  /// 표준 출력은 프로토콜 메시지가 쓰므로, 프로그램의 출력은 모아 두었다가 output 이벤트로 보냅니다.
+  program_output: MemoryOutput,
+  program_path: String,
+  valid_lines: Option<BTreeSet<u32>>,
+  breakpoints: BTreeSet<u32>,
+  stop_on_entry: bool,
+  configured: bool,
+  started: bool,
+}
+
+impl<R: BufRead, W: Write> DapServer<R, W> {
+  pub fn new(input: R, output: W) -> DapServer<R, W> {
+    DapServer {
+      input,
+      output,
+      seq: 0,
+      machine: None,
+      program_output: MemoryOutput::new(),
+      program_path: String::new(),
+      valid_lines: None,
+      breakpoints: BTreeSet::new(),
+      stop_on_entry: false,
+      configured: false,
+      started: false,
+    }
+  }
+
+  pub fn run(&mut self) -> io::Result<()> {
+    while let Some(message) = self.read_message()? {
+      if message["type"] != "request" {
+        continue;
+      }
+
+      let command = message["command"].as_str().unwrap_or_default().to_string();
+      let request_seq = message["seq"].as_i64().unwrap_or_default();
+      let arguments = &message["arguments"];
+
+      let result = match command.as_str() {
+        "initialize" => Ok(json!({ "supportsConfigurationDoneRequest": true })),
+        "launch" => self.launch(arguments),
+        "setBreakpoints" => Ok(self.set_breakpoints(arguments)),
+        "configurationDone" => {
+          self.configured = true;
+          Ok(Value::Null)
+        }
+        "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })),
+        "stackTrace" => Ok(self.stack_trace()),
+        "scopes" => Ok(self.scopes(arguments)),
+        "variables" => Ok(self.variables(arguments)),
+        "continue" | "next" | "stepIn" | "stepOut" => {
+          if self.machine.is_some() {
+            Ok(json!({ "allThreadsContinued": true }))
+          } else {
+            Err(String::from("실행 중인 프로그램이 없습니다."))
+          }
+        }
+        "disconnect" => Ok(Value::Null),
+        _ => Err(format!("지원하지 않는 요청입니다 : {}", command)),
+      };
+
+      self.respond(request_seq, &command, result)?;
+
+      match command.as_str() {
+        "initialize" => self.send_event("initialized", Value::Null)?,
+        "launch" | "configurationDone" => self.start()?,
+        "continue" => self.resume(StepMode::Continue)?,
+        "next" => self.resume(StepMode::StepOver)?,
+        "stepIn" => self.resume(StepMode::StepInto)?,
+        "stepOut" => self.resume(StepMode::StepOut)?,
+        "disconnect" => return Ok(()),
+        _ => {}
+      }
+    }
+
+    Ok(())
+  }
+
+  fn launch(&mut self, arguments: &Value) -> Result<Value, String> {
+    let path = match arguments["program"].as_str() {
+      Some(path) => path,
+      None => {
+        return Err(String::from(
+          "실행할 프로그램의 경로(program)가 필요합니다.",
+        ))
+      }
+    };
+    let data = fs::read(path).map_err(|_| format!("파일을 찾을 수 없습니다 : {}", path))?;
+    let (_, mut parsed) = program(&data[..]).map_err(|err| format!("{:?}", err))?;
+
+    let search_path = Path::new(path).parent().map(Path::to_path_buf);
+    // 표준 입출력으로는 프로토콜 메시지가 오가므로, 모듈과 프로그램에는 따로 모으는 출력과 빈 입력을 줍니다.
+    let mut loader = ModuleLoader::new(search_path.into_iter().collect());
+    loader.set_output(Box::new(self.program_output.clone()));
+    loader.set_input(Box::new(io::empty()));
+    let globals = loader.globals(&mut parsed).map_err(|err| err.to_string())?;
+    let mut machine = Machine::new(
+      globals,
+      parsed.global_var_names.clone(),
+      parsed.josa_table.clone(),
+    );
+    machine.set_output(Box::new(self.program_output.clone()));
+    machine.set_input(Box::new(io::empty()));
+    self.valid_lines = Some(parsed.line_numbers());
+    machine.load(StackFrame::new(parsed.code, parsed.const_table));
+
+    self.machine = Some(machine);
+    self.program_path = path.to_string();
+    self.stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+    Ok(Value::Null)
+  }
+
+  fn set_breakpoints(&mut self, arguments: &Value) -> Value {
+    let lines: Vec<u32> = arguments["breakpoints"]
+      .as_array()
+      .map(|list| {
+        list
+          .iter()
+          .filter_map(|x| x["line"].as_u64())
+          .map(|x| x as u32)
+          .collect()
+      })
+      .unwrap_or_default();
+
+    self.breakpoints = lines.iter().cloned().collect();
+
+    let breakpoints: Vec<Value> = lines
+      .iter()
+      .map(|line| {
+        let verified = self
+          .valid_lines
+          .as_ref()
+          .is_none_or(|valid_lines| valid_lines.contains(line));
+        json!({ "verified": verified, "line": line })
+      })
+      .collect();
+
+    json!({ "breakpoints": breakpoints })
+  }
+
+  fn stack_trace(&self) -> Value {
+    let machine = match &self.machine {
+      Some(machine) => machine,
+      None => return json!({ "stackFrames": [], "totalFrames": 0 }),
+    };
+    let frames = machine.call_stack();
+
+    let stack_frames: Vec<Value> = frames
+      .iter()
+      .enumerate()
+      .rev()
+      .map(|(depth, frame)| {
+        let line = frame.code.get(frame.ip).map_or(0, |x| x.line_number);
+        let name = if depth == 0 {
+          String::from(TOP_LEVEL_LABEL)
+        } else {
+          format!("함수({})", machine.local_names(frame).join(", "))
+        };
+
+        json!({
+          "id": depth,
+          "name": name,
+          "line": line,
+          "column": 1,
+          "source": { "path": self.program_path },
+        })
+      })
+      .collect();
+
+    json!({ "stackFrames": stack_frames, "totalFrames": frames.len() })
+  }
+
+  fn scopes(&self, arguments: &Value) -> Value {
+    let frame_id = arguments["frameId"].as_i64().unwrap_or_default();
+    let reference = |kind| frame_id * SCOPE_KINDS + kind;
+
+    json!({
+      "scopes": [
+        { "name": "인수", "variablesReference": reference(SCOPE_LOCALS), "expensive": false },
+        { "name": "자유 변수", "variablesReference": reference(SCOPE_FREE_VARS), "expensive": false },
+        { "name": "전역 변수", "variablesReference": reference(SCOPE_GLOBALS), "expensive": false },
+      ]
+    })
+  }
+
+  fn variables(&self, arguments: &Value) -> Value {
+    let machine = match &self.machine {
+      Some(machine) => machine,
+      None => return json!({ "variables": [] }),
+    };
+
+    let reference = arguments["variablesReference"].as_i64().unwrap_or_default();
+    if reference <= 0 {
+      return json!({ "variables": [] });
+    }
+
+    let frame_id = ((reference - 1) / SCOPE_KINDS) as usize;
+    let kind = (reference - 1) % SCOPE_KINDS + 1;
+
+    let variable = |name: String, value: String| json!({ "name": name, "value": value, "variablesReference": 0 });
+
+    let variables: Vec<Value> = match (kind, machine.call_stack().get(frame_id)) {
+      (SCOPE_LOCALS, Some(frame)) => machine
+        .locals(frame)
+        .into_iter()
+        .map(|(name, value)| variable(name.to_string(), format!("{:?}", value)))
+        .collect(),
+      (SCOPE_FREE_VARS, Some(frame)) => frame
+        .free_vars
+        .iter()
+        .enumerate()
+        .map(|(index, value)| variable(format!("[{}]", index), format!("{:?}", value)))
+        .collect(),
+      (SCOPE_GLOBALS, _) => machine
+        .global_var_names()
+        .iter()
+        .filter_map(|name| {
+          let value = machine.global(name)??;
+          Some(variable(name.clone(), format!("{:?}", value)))
+        })
+        .collect(),
+      _ => Vec::new(),
+    };
+
+    json!({ "variables": variables })
+  }
+
+  /// `launch`와 `configurationDone`을 모두 받은 뒤에 실행을 시작합니다.
+  fn start(&mut self) -> io::Result<()> {
+    if self.started || !self.configured || self.machine.is_none() {
+      return Ok(());
+    }
+    self.started = true;
+
+    let at_breakpoint = self
+      .machine
+      .as_ref()
+      .and_then(Machine::current_line)
+      .is_some_and(|line| self.breakpoints.contains(&line));
+
+    if self.stop_on_entry || at_breakpoint {
+      let reason = if self.stop_on_entry {
+        "entry"
+      } else {
+        "breakpoint"
+      };
+      self.send_event(
+        "stopped",
+        json!({ "reason": reason, "threadId": THREAD_ID }),
+      )
+    } else {
+      self.resume(StepMode::Continue)
+    }
+  }
+
+  fn resume(&mut self, mode: StepMode) -> io::Result<()> {
+    let machine = match &mut self.machine {
+      Some(machine) => machine,
+      None => return Ok(()),
+    };
+
+    let reason = run_until(machine, mode, &self.breakpoints);
+
+    let output = self.program_output.take();
+    if !output.is_empty() {
+      self.send_event("output", json!({ "category": "stdout", "output": output }))?;
+    }
+
+    match reason {
+      StopReason::Breakpoint => self.send_event(
+        "stopped",
+        json!({ "reason": "breakpoint", "threadId": THREAD_ID }),
+      ),
+      StopReason::Step => self.send_event(
+        "stopped",
+        json!({ "reason": "step", "threadId": THREAD_ID }),
+      ),
+      StopReason::Finished => self.terminate(0),
+      StopReason::Error(line_number, err) => {
+        let message = format!("{}번째 라인 에서 에러 발생 : {}\n", line_number, err);
+        self.send_event("output", json!({ "category": "stderr", "output": message }))?;
+        self.terminate(1)
+      }
+    }
+  }
+
+  fn terminate(&mut self, exit_code: i32) -> io::Result<()> {
+    self.machine = None;
+    self.send_event("exited", json!({ "exitCode": exit_code }))?;
+    self.send_event("terminated", Value::Null)
+  }
+
+  fn read_message(&mut self) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+
+    loop {
+      let mut header = String::new();
+      if self.input.read_line(&mut header)? == 0 {
+        return Ok(None);
+      }
+
+      let header = header.trim();
+      if header.is_empty() {
+        if content_length.is_some() {
+          break;
+        }
+        continue;
+      }
+
+      if let Some(length) = header.strip_prefix("Content-Length:") {
+        content_length = length.trim().parse::<usize>().ok();
+      }
+    }
+
+    let mut body = vec![0; content_length.unwrap()];
+    self.input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+      .map(Some)
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+  }
+
+  fn send(&mut self, mut message: Value) -> io::Result<()> {
+    self.seq += 1;
+    message["seq"] = json!(self.seq);
+
+    let body = message.to_string();
+    write!(
+      self.output,
+      "Content-Length: {}\r\n\r\n{}",
+      body.len(),
+      body
+    )?;
+    self.output.flush()
+  }
+
+  fn respond(
+    &mut self,
+    request_seq: i64,
+    command: &str,
+    result: Result<Value, String>,
+  ) -> io::Result<()> {
+    let mut message = json!({
+      "type": "response",
+      "request_seq": request_seq,
+      "command": command,
+      "success": result.is_ok(),
+    });
+
+    match result {
+      Ok(Value::Null) => {}
+      Ok(body) => message["body"] = body,
+      Err(error) => message["message"] = json!(error),
+    }
+
+    self.send(message)
+  }
+
+  fn send_event(&mut self, event: &str, body: Value) -> io::Result<()> {
+    let mut message = json!({ "type": "event", "event": event });
+    if !body.is_null() {
+      message["body"] = body;
+    }
+
+    self.send(message)
+  }
+}
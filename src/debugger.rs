@@ -0,0 +1,382 @@
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+
+use crate::error::HaneulError;
+use crate::machine::Machine;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepMode {
+  Continue,
+  StepInto,
+  StepOver,
+  StepOut,
+}
+
+#[derive(Debug)]
+pub enum StopReason {
+  Breakpoint,
+  Step,
+  Finished,
+  Error(u32, Box<HaneulError>),
+}
+
+/// `mode`에 따라 멈춰야 할 곳까지 실행합니다. 어느 경우든 새로 들어선 줄에
+/// 중단점이 걸려 있으면 그 자리에서 멈춥니다. 함수에서 돌아와 호출한 줄로
+/// 되돌아가는 것은 새로 들어선 것으로 치지 않습니다.
+pub fn run_until(machine: &mut Machine, mode: StepMode, breakpoints: &BTreeSet<u32>) -> StopReason {
+  let start_depth = machine.call_stack().len();
+  let (mut last_depth, mut last_line) = (start_depth, machine.current_line());
+
+  loop {
+    if let Err((line_number, err)) = machine.step() {
+      return StopReason::Error(line_number, Box::new(err));
+    }
+
+    let depth = machine.call_stack().len();
+    let line = match machine.current_line() {
+      Some(line) => line,
+      None => return StopReason::Finished,
+    };
+
+    let returned = depth < last_depth;
+    let entered = depth > last_depth || (depth == last_depth && Some(line) != last_line);
+    last_depth = depth;
+    last_line = Some(line);
+
+    if entered && breakpoints.contains(&line) {
+      return StopReason::Breakpoint;
+    }
+
+    let stop = match mode {
+      StepMode::Continue => false,
+      StepMode::StepInto => entered || returned,
+      StepMode::StepOver => (entered && depth == start_depth) || depth < start_depth,
+      StepMode::StepOut => depth < start_depth,
+    };
+
+    if stop {
+      return StopReason::Step;
+    }
+  }
+}
+
+const HELP: &str = "\
+  break, b <줄>      <줄>에 중단점을 설정합니다.
+  delete, d <줄>     <줄>의 중단점을 삭제합니다.
+  step, s            다음 줄까지 실행합니다. 함수 호출이 있으면 함수 안으로 들어갑니다.
+  next, n            다음 줄까지 실행합니다. 함수 호출은 건너뜁니다.
+  finish, f          현재 함수가 끝날 때까지 실행합니다.
+  continue, c        다음 중단점까지 실행합니다.
+  backtrace, bt      호출 스택을 출력합니다.
+  stack              피연산자 스택을 출력합니다.
+  locals             현재 함수의 인수를 출력합니다.
+  free               현재 함수의 자유 변수를 출력합니다.
+  print, p <이름>    전역 변수의 값을 출력합니다.
+  quit, q            디버거를 종료합니다.";
+
+/// 표준 입출력(또는 임의의 입출력)으로 명령을 주고받는 줄 단위 디버거입니다.
+pub struct Debugger<R, W> {
+  machine: Machine,
+  input: R,
+  output: W,
+  breakpoints: BTreeSet<u32>,
+  terminated: bool,
+}
+
+impl<R: BufRead, W: Write> Debugger<R, W> {
+  /// 실행할 프레임이 이미 올라가 있는 `machine`으로 디버거를 만듭니다.
+  pub fn new(machine: Machine, input: R, output: W) -> Debugger<R, W> {
+    Debugger {
+      terminated: machine.is_finished(),
+      machine,
+      input,
+      output,
+      breakpoints: BTreeSet::new(),
+    }
+  }
+
+  pub fn run(&mut self) -> io::Result<()> {
+    writeln!(
+      self.output,
+      "하늘 디버거입니다. 명령어 목록을 보려면 'help'를 입력하세요."
+    )?;
+    self.print_position()?;
+
+    loop {
+      write!(self.output, "(하늘) ")?;
+      self.output.flush()?;
+
+      let mut line = String::new();
+      if self.input.read_line(&mut line)? == 0 {
+        writeln!(self.output)?;
+        return Ok(());
+      }
+
+      let mut words = line.split_whitespace();
+      let command = match words.next() {
+        Some(command) => command,
+        None => continue,
+      };
+      let argument = words.next();
+
+      match command {
+        "break" | "b" => self.set_breakpoint(argument, true)?,
+        "delete" | "d" => self.set_breakpoint(argument, false)?,
+        "step" | "s" => self.resume(StepMode::StepInto)?,
+        "next" | "n" => self.resume(StepMode::StepOver)?,
+        "finish" | "f" => self.resume(StepMode::StepOut)?,
+        "continue" | "c" => self.resume(StepMode::Continue)?,
+        "backtrace" | "bt" => self.print_backtrace()?,
+        "stack" => self.print_stack()?,
+        "locals" => self.print_locals()?,
+        "free" => self.print_free_vars()?,
+        "print" | "p" => self.print_global(argument)?,
+        "help" | "h" => writeln!(self.output, "{}", HELP)?,
+        "quit" | "q" => return Ok(()),
+        _ => writeln!(
+          self.output,
+          "알 수 없는 명령어입니다 : {}. 'help'로 명령어 목록을 볼 수 있습니다.",
+          command
+        )?,
+      }
+    }
+  }
+
+  fn set_breakpoint(&mut self, argument: Option<&str>, insert: bool) -> io::Result<()> {
+    let line = match argument.and_then(|x| x.parse::<u32>().ok()) {
+      Some(line) => line,
+      None => return writeln!(self.output, "줄 번호를 입력해주세요."),
+    };
+
+    if insert {
+      self.breakpoints.insert(line);
+      writeln!(self.output, "{}번째 줄에 중단점을 설정했습니다.", line)
+    } else if self.breakpoints.remove(&line) {
+      writeln!(self.output, "{}번째 줄의 중단점을 삭제했습니다.", line)
+    } else {
+      writeln!(self.output, "{}번째 줄에는 중단점이 없습니다.", line)
+    }
+  }
+
+  fn resume(&mut self, mode: StepMode) -> io::Result<()> {
+    if self.terminated {
+      return writeln!(self.output, "프로그램이 이미 종료되었습니다.");
+    }
+
+    match run_until(&mut self.machine, mode, &self.breakpoints) {
+      StopReason::Breakpoint => {
+        write!(self.output, "중단점 : ")?;
+        self.print_position()
+      }
+      StopReason::Step => self.print_position(),
+      StopReason::Finished => {
+        self.terminated = true;
+        writeln!(self.output, "프로그램이 정상 종료되었습니다.")
+      }
+      StopReason::Error(line_number, err) => {
+        self.terminated = true;
+        writeln!(
+          self.output,
+          "{}번째 라인 에서 에러 발생 : {}",
+          line_number, err
+        )
+      }
+    }
+  }
+
+  fn print_position(&mut self) -> io::Result<()> {
+    match self.machine.current_line() {
+      Some(line) => writeln!(
+        self.output,
+        "{}번째 줄에서 멈췄습니다. (호출 깊이 {})",
+        line,
+        self.machine.call_stack().len()
+      ),
+      None => writeln!(self.output, "실행할 코드가 없습니다."),
+    }
+  }
+
+  fn print_backtrace(&mut self) -> io::Result<()> {
+    let frames = self.machine.call_stack();
+    if frames.is_empty() {
+      return writeln!(self.output, "(빈 호출 스택)");
+    }
+
+    for (depth, frame) in frames.iter().enumerate().rev() {
+      let line = frame.code.get(frame.ip).map_or(0, |x| x.line_number);
+      writeln!(self.output, "  #{} {}번째 줄", depth, line)?;
+    }
+    Ok(())
+  }
+
+  fn print_stack(&mut self) -> io::Result<()> {
+    let stack = self.machine.operand_stack();
+    if stack.is_empty() {
+      return writeln!(self.output, "(빈 스택)");
+    }
+
+    for (index, value) in stack.iter().enumerate() {
+      writeln!(self.output, "  [{}] {:?}", index, value)?;
+    }
+    Ok(())
+  }
+
+  fn print_locals(&mut self) -> io::Result<()> {
+    let locals = match self.machine.call_stack().last() {
+      Some(frame) => self.machine.locals(frame),
+      None => Vec::new(),
+    };
+
+    if locals.is_empty() {
+      return writeln!(self.output, "(인수 없음)");
+    }
+
+    for (name, value) in locals {
+      writeln!(self.output, "  {} = {:?}", name, value)?;
+    }
+    Ok(())
+  }
+
+  fn print_free_vars(&mut self) -> io::Result<()> {
+    let free_vars = match self.machine.call_stack().last() {
+      Some(frame) => &frame.free_vars[..],
+      None => &[],
+    };
+
+    if free_vars.is_empty() {
+      return writeln!(self.output, "(자유 변수 없음)");
+    }
+
+    for (index, value) in free_vars.iter().enumerate() {
+      writeln!(self.output, "  [{}] {:?}", index, value)?;
+    }
+    Ok(())
+  }
+
+  fn print_global(&mut self, argument: Option<&str>) -> io::Result<()> {
+    let name = match argument {
+      Some(name) => name,
+      None => return writeln!(self.output, "변수 이름을 입력해주세요."),
+    };
+
+    match self.machine.global(name) {
+      Some(Some(value)) => writeln!(self.output, "{} = {:?}", name, value),
+      Some(None) => writeln!(
+        self.output,
+        "변수 '{}'에는 아직 값이 정해지지 않았습니다.",
+        name
+      ),
+      None => writeln!(self.output, "변수 '{}'을(를) 찾을 수 없습니다.", name),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use crate::constant::Constant;
+  use crate::funcobject::FuncObject;
+  use crate::instruction::Instruction;
+  use crate::josa::{JosaMap, JosaTable};
+  use crate::machine::StackFrame;
+  use crate::opcode::{BinaryOp, Opcode};
+
+  fn inst(line_number: u32, opcode: Opcode) -> Instruction {
+    Instruction {
+      line_number,
+      opcode,
+    }
+  }
+
+  fn debug(commands: &str) -> String {
+    let mut josa_table = JosaTable::new();
+    let eul = josa_table.intern("을");
+
+    // 10: 두배(을) = 을 + 을
+    let double = Constant::Function {
+      josa_map: Rc::new(JosaMap::new(vec![eul])),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![
+          inst(10, Opcode::Load(0)),
+          inst(10, Opcode::Load(0)),
+          inst(10, Opcode::BinaryOp(BinaryOp::Add)),
+        ]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+
+    // 1: 두배 = ...
+    // 2: 결과 = 두배(21을)
+    // 3: 결과
+    let code = vec![
+      inst(1, Opcode::Push(0)),
+      inst(1, Opcode::StoreGlobal(0)),
+      inst(2, Opcode::Push(1)),
+      inst(2, Opcode::LoadGlobal(0)),
+      inst(2, Opcode::Call(vec![eul])),
+      inst(2, Opcode::StoreGlobal(1)),
+      inst(3, Opcode::LoadGlobal(1)),
+      inst(3, Opcode::Pop),
+    ];
+
+    let mut machine = Machine::new(
+      Vec::new(),
+      vec![String::from("두배"), String::from("결과")],
+      josa_table,
+    );
+    machine.load(StackFrame::new(code, vec![double, Constant::Integer(21)]));
+
+    let mut output = Vec::new();
+    Debugger::new(machine, commands.as_bytes(), &mut output)
+      .run()
+      .unwrap();
+    String::from_utf8(output).unwrap()
+  }
+
+  #[test]
+  fn stop_at_breakpoint_and_step_out() {
+    let output = debug("b 10\nc\nlocals\nbt\nf\nn\np 결과\nc\nc\n");
+    let expected = "\
+하늘 디버거입니다. 명령어 목록을 보려면 'help'를 입력하세요.
+1번째 줄에서 멈췄습니다. (호출 깊이 1)
+(하늘) 10번째 줄에 중단점을 설정했습니다.
+(하늘) 중단점 : 10번째 줄에서 멈췄습니다. (호출 깊이 2)
+(하늘)   을 = Integer(21)
+(하늘)   #1 10번째 줄
+  #0 2번째 줄
+(하늘) 2번째 줄에서 멈췄습니다. (호출 깊이 1)
+(하늘) 3번째 줄에서 멈췄습니다. (호출 깊이 1)
+(하늘) 결과 = Integer(42)
+(하늘) 프로그램이 정상 종료되었습니다.
+(하늘) 프로그램이 이미 종료되었습니다.
+(하늘) \n";
+    assert_eq!(output, expected);
+  }
+
+  #[test]
+  fn step_over_and_into_calls() {
+    let output = debug("n\nn\np 결과\ns\ns\nstack\n");
+    let expected = "\
+하늘 디버거입니다. 명령어 목록을 보려면 'help'를 입력하세요.
+1번째 줄에서 멈췄습니다. (호출 깊이 1)
+(하늘) 2번째 줄에서 멈췄습니다. (호출 깊이 1)
+(하늘) 3번째 줄에서 멈췄습니다. (호출 깊이 1)
+(하늘) 결과 = Integer(42)
+(하늘) 프로그램이 정상 종료되었습니다.
+(하늘) 프로그램이 이미 종료되었습니다.
+(하늘) (빈 스택)
+(하늘) \n";
+    assert_eq!(output, expected);
+
+    let output = debug("n\ns\nstack\nd 10\n");
+    assert!(output.contains("(하늘) 10번째 줄에서 멈췄습니다. (호출 깊이 2)"));
+    assert!(output.contains("(하늘)   [0] Integer(21)\n(하늘) "));
+    assert!(output.contains("10번째 줄에는 중단점이 없습니다."));
+  }
+}
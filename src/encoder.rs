@@ -0,0 +1,201 @@
+use std::cmp::Ordering;
+use std::error;
+use std::fmt;
+
+use crate::constant::Constant;
+use crate::funcobject::FuncObject;
+use crate::instruction::Instruction;
+use crate::josa::{JosaId, JosaTable};
+use crate::opcode::{BinaryOp, Opcode, UnaryOp};
+use crate::program::{Import, Program};
+
+#[derive(Debug)]
+pub enum EncodeError {
+  /// 목록과 내장 함수는 실행 중에만 만들어지는 값이라 바이트코드의 상수로 쓸 수 없습니다.
+  UnsupportedConstant { value: Constant },
+}
+
+impl fmt::Display for EncodeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      EncodeError::UnsupportedConstant { value } => write!(
+        f,
+        "{} 값은 바이트코드의 상수로 쓸 수 없습니다 : {:?}",
+        value.type_name(),
+        value
+      ),
+    }
+  }
+}
+
+impl error::Error for EncodeError {}
+
+/// `parser::program`이 읽는 바이트코드 형식으로 프로그램을 씁니다.
+/// 가져오거나 내보내는 것이 없으면 모듈 정보를 쓰지 않으므로, 예전 형식의 파일과 같은 바이트가 됩니다.
+pub fn encode(program: &Program) -> Result<Vec<u8>, EncodeError> {
+  let mut encoder = Encoder {
+    bytes: Vec::new(),
+    josa_table: &program.josa_table,
+  };
+
+  encoder.strings(&program.global_var_names);
+  encoder.list(&program.const_table, Encoder::constant)?;
+  encoder.instructions(&program.code);
+
+  if !program.imports.is_empty() || !program.exports.is_empty() {
+    encoder.u64(program.imports.len() as u64);
+    for Import { module, names } in &program.imports {
+      encoder.string(module);
+      encoder.strings(names);
+    }
+    encoder.strings(&program.exports);
+  }
+
+  Ok(encoder.bytes)
+}
+
+struct Encoder<'a> {
+  bytes: Vec<u8>,
+  josa_table: &'a JosaTable,
+}
+
+impl<'a> Encoder<'a> {
+  fn u8(&mut self, value: u8) {
+    self.bytes.push(value);
+  }
+
+  fn u32(&mut self, value: u32) {
+    self.bytes.extend_from_slice(&value.to_be_bytes());
+  }
+
+  fn u64(&mut self, value: u64) {
+    self.bytes.extend_from_slice(&value.to_be_bytes());
+  }
+
+  fn list<T>(
+    &mut self,
+    items: &[T],
+    mut write: impl FnMut(&mut Self, &T) -> Result<(), EncodeError>,
+  ) -> Result<(), EncodeError> {
+    self.u64(items.len() as u64);
+    for item in items {
+      write(self, item)?;
+    }
+    Ok(())
+  }
+
+  fn strings(&mut self, items: &[String]) {
+    self.u64(items.len() as u64);
+    for item in items {
+      self.string(item);
+    }
+  }
+
+  fn instructions(&mut self, code: &[Instruction]) {
+    self.u64(code.len() as u64);
+    for inst in code {
+      self.instruction(inst);
+    }
+  }
+
+  /// 문자열의 길이는 바이트가 아닌 문자의 개수입니다.
+  fn string(&mut self, text: &str) {
+    self.u64(text.chars().count() as u64);
+    self.bytes.extend_from_slice(text.as_bytes());
+  }
+
+  fn josa_list(&mut self, josa_list: &[JosaId]) {
+    self.u8(josa_list.len() as u8);
+    for josa in josa_list {
+      let name = self.josa_table.name(*josa);
+      self.bytes.push(name.chars().count() as u8);
+      self.bytes.extend_from_slice(name.as_bytes());
+    }
+  }
+
+  fn instruction(&mut self, inst: &Instruction) {
+    self.u32(inst.line_number);
+    match &inst.opcode {
+      Opcode::Push(v) => self.operand(0, *v),
+      Opcode::Pop => self.u8(1),
+      Opcode::Load(v) => self.operand(2, *v),
+      Opcode::LoadDeref(v) => self.operand(3, *v),
+      Opcode::StoreGlobal(v) => self.operand(4, *v),
+      Opcode::LoadGlobal(v) => self.operand(5, *v),
+      Opcode::Call(josa_list) => {
+        self.u8(6);
+        self.josa_list(josa_list);
+      }
+      Opcode::Jmp(v) => self.operand(7, *v),
+      Opcode::PopJmpIfFalse(v) => self.operand(8, *v),
+      Opcode::FreeVarLocal(v) => {
+        self.u8(9);
+        self.u8(*v);
+      }
+      Opcode::FreeVarFree(v) => {
+        self.u8(10);
+        self.u8(*v);
+      }
+      Opcode::BinaryOp(op) => self.u8(match op {
+        BinaryOp::Add => 11,
+        BinaryOp::Subtract => 12,
+        BinaryOp::Multiply => 13,
+        BinaryOp::Divide => 14,
+        BinaryOp::Mod => 15,
+        BinaryOp::Cmp(Ordering::Equal) => 16,
+        BinaryOp::Cmp(Ordering::Less) => 17,
+        BinaryOp::Cmp(Ordering::Greater) => 18,
+      }),
+      Opcode::UnaryOp(UnaryOp::Negate) => self.u8(19),
+    }
+  }
+
+  fn operand(&mut self, opcode_index: u8, value: u32) {
+    self.u8(opcode_index);
+    self.u32(value);
+  }
+
+  fn constant(&mut self, value: &Constant) -> Result<(), EncodeError> {
+    match value {
+      Constant::None => self.u8(0),
+      Constant::Integer(v) => {
+        self.u8(1);
+        self.bytes.extend_from_slice(&v.to_be_bytes());
+      }
+      Constant::Real(v) => {
+        self.u8(2);
+        self.bytes.extend_from_slice(&v.to_be_bytes());
+      }
+      Constant::Char(v) => {
+        self.u8(3);
+        self.u32(*v as u32);
+      }
+      Constant::Boolean(v) => {
+        self.u8(4);
+        self.u8(*v as u8);
+      }
+      Constant::Str(v) => {
+        self.u8(7);
+        self.string(v);
+      }
+      Constant::Function {
+        josa_map,
+        rest_args,
+        func_object: FuncObject::CodeObject {
+          code, const_table, ..
+        },
+      } => {
+        self.u8(if rest_args.is_some() { 6 } else { 5 });
+        self.josa_list(&josa_map.keys().collect::<Vec<_>>());
+        self.list(const_table, Encoder::constant)?;
+        self.instructions(code);
+      }
+      Constant::List(_) | Constant::Function { .. } => {
+        return Err(EncodeError::UnsupportedConstant {
+          value: value.clone(),
+        })
+      }
+    }
+    Ok(())
+  }
+}
@@ -2,8 +2,82 @@ use std::error;
 use std::fmt;
 
 use crate::constant::Constant;
+use crate::hangul::jamo_distance;
 use crate::opcode::{BinaryOp, UnaryOp};
 
+/// 호출 시점에 함수가 가진 조사 목록과 그 중 이미 적용된 조사입니다.
+/// 같은 조사가 두 번 나올 수 있으므로 적용 여부는 조사 목록의 자리마다 따로 둡니다.
+#[derive(Debug)]
+pub struct JosaSignature {
+  pub josa_list: Vec<String>,
+  pub applied: Vec<bool>,
+  pub variadic: bool,
+}
+
+impl JosaSignature {
+  fn slots(&self, applied: bool) -> Vec<&String> {
+    self
+      .josa_list
+      .iter()
+      .zip(&self.applied)
+      .filter(|(_, x)| **x == applied)
+      .map(|(josa, _)| josa)
+      .collect()
+  }
+
+  pub fn applied(&self) -> Vec<&String> {
+    self.slots(true)
+  }
+
+  pub fn missing(&self) -> Vec<&String> {
+    self.slots(false)
+  }
+
+  /// 아직 적용되지 않은 조사 중 `josa`와 가장 비슷한 조사를 찾습니다.
+  pub fn suggest(&self, josa: &str) -> Option<String> {
+    self
+      .missing()
+      .into_iter()
+      .filter(|candidate| candidate.as_str() != josa)
+      .map(|candidate| (jamo_distance(candidate, josa), candidate))
+      .filter(|(distance, _)| *distance <= 2)
+      .min_by_key(|(distance, _)| *distance)
+      .map(|(_, candidate)| candidate.clone())
+  }
+}
+
+impl fmt::Display for JosaSignature {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> fmt::Result {
+    let join = |list: Vec<&String>| {
+      if list.is_empty() {
+        String::from("(없음)")
+      } else {
+        list
+          .iter()
+          .map(|josa| josa.as_str())
+          .collect::<Vec<_>>()
+          .join(", ")
+      }
+    };
+
+    let mut josa_list = join(self.josa_list.iter().collect());
+    if self.variadic {
+      josa_list.push_str(" (+ 나머지 인수)");
+    }
+
+    writeln!(f, "  함수의 조사 : {}", josa_list)?;
+    writeln!(f, "  이미 적용된 조사 : {}", join(self.applied()))?;
+    write!(f, "  아직 적용되지 않은 조사 : {}", join(self.missing()))
+  }
+}
+
+fn write_suggestion(f: &mut std::fmt::Formatter, suggestion: &Option<String>) -> fmt::Result {
+  match suggestion {
+    Some(josa) => write!(f, "\n  혹시 조사 '{}'를 사용하려고 하셨나요?", josa),
+    None => Ok(()),
+  }
+}
+
 #[derive(Debug)]
 pub enum HaneulError {
   UnboundVariable {
@@ -12,6 +86,7 @@ pub enum HaneulError {
   TooManyArgs {
     actual_arity: u8,
     given_arity: u8,
+    signature: JosaSignature,
   },
   NotCallable {
     value: Constant,
@@ -23,16 +98,89 @@ pub enum HaneulError {
     value: Constant,
     op: UnaryOp,
   },
+  /// 두 피연산자를 모두 담으면 에러 값이 커지므로 상자에 넣어 둡니다.
   InvalidBinaryOp {
-    lhs: Constant,
-    rhs: Constant,
+    lhs: Box<Constant>,
+    rhs: Box<Constant>,
     op: BinaryOp,
   },
   AlreadyAppliedJosa {
     josa: String,
+    signature: JosaSignature,
+    suggestion: Option<String>,
   },
   UnboundJosa {
     josa: String,
+    signature: JosaSignature,
+    suggestion: Option<String>,
+  },
+  OutOfFuel {
+    executed: u64,
+  },
+  DeadlineExceeded {
+    executed: u64,
+  },
+  StackOverflow {
+    limit: usize,
+  },
+  CallDepthExceeded {
+    limit: usize,
+  },
+  HeapLimitExceeded {
+    limit: usize,
+    size: usize,
+  },
+  OutputFailed {
+    reason: String,
+  },
+  InputFailed {
+    reason: String,
+  },
+  EndOfInput,
+  InvalidInput {
+    expected: &'static str,
+    input: String,
+  },
+  InvalidArgument {
+    function: &'static str,
+    value: Constant,
+  },
+  DomainError {
+    function: &'static str,
+    value: Constant,
+  },
+  IntegerOverflow {
+    function: &'static str,
+  },
+  ParseFailed {
+    expected: &'static str,
+    text: String,
+  },
+  InvalidFormat {
+    template: String,
+    reason: String,
+  },
+  PermissionDenied {
+    access: &'static str,
+    path: String,
+  },
+  FileFailed {
+    path: String,
+    reason: String,
+  },
+  ModuleNotFound {
+    module: String,
+  },
+  ImportCycle {
+    cycle: Vec<String>,
+  },
+  MissingExport {
+    module: String,
+    name: String,
+  },
+  ModuleFailed {
+    module: String,
+    reason: String,
   },
 }
 
@@ -46,10 +194,11 @@ impl fmt::Display for HaneulError {
       HaneulError::TooManyArgs {
         actual_arity,
         given_arity,
+        signature,
       } => write!(
         f,
-        "인수 {}개를 받는 함수인데 {}개가 주어졌습니다.",
-        actual_arity, given_arity
+        "인수 {}개를 받는 함수인데 {}개가 주어졌습니다.\n{}",
+        actual_arity, given_arity, signature
       ),
 
       HaneulError::NotCallable { value } => write!(
@@ -78,14 +227,163 @@ impl fmt::Display for HaneulError {
         rhs.type_name(),
         op.op_name()
       ),
-      HaneulError::AlreadyAppliedJosa { josa } => {
-        write!(f, "조사 '{}'는 이미 적용된 조사입니다.", josa)
+      HaneulError::AlreadyAppliedJosa {
+        josa,
+        signature,
+        suggestion,
+      } => {
+        write!(
+          f,
+          "조사 '{}'는 이미 적용된 조사입니다.\n{}",
+          josa, signature
+        )?;
+        write_suggestion(f, suggestion)
+      }
+      HaneulError::UnboundJosa {
+        josa,
+        signature,
+        suggestion,
+      } => {
+        write!(
+          f,
+          "이 함수에서 조사 '{}'를 찾을 수 없습니다.\n{}",
+          josa, signature
+        )?;
+        write_suggestion(f, suggestion)
+      }
+      HaneulError::OutOfFuel { executed } => write!(
+        f,
+        "명령어 실행 한도를 모두 사용했습니다. (실행한 명령어 {}개)",
+        executed
+      ),
+      HaneulError::DeadlineExceeded { executed } => write!(
+        f,
+        "실행 제한 시간을 초과했습니다. (실행한 명령어 {}개)",
+        executed
+      ),
+      HaneulError::StackOverflow { limit } => {
+        write!(f, "스택에 값을 {}개보다 많이 쌓을 수 없습니다.", limit)
+      }
+      HaneulError::CallDepthExceeded { limit } => write!(
+        f,
+        "함수 호출 깊이가 제한({})을 넘었습니다. 재귀 호출이 끝나는지 확인해주세요.",
+        limit
+      ),
+      HaneulError::HeapLimitExceeded { limit, size } => write!(
+        f,
+        "값들이 차지하는 메모리({}바이트)가 제한({}바이트)을 넘었습니다.",
+        size, limit
+      ),
+      HaneulError::OutputFailed { reason } => write!(f, "출력할 수 없습니다 : {}", reason),
+      HaneulError::InputFailed { reason } => write!(f, "입력을 읽을 수 없습니다 : {}", reason),
+      HaneulError::EndOfInput => write!(f, "더 이상 읽을 입력이 없습니다."),
+      HaneulError::InvalidInput { expected, input } => write!(
+        f,
+        "입력 '{}'을(를) {}(으)로 읽을 수 없습니다.",
+        input, expected
+      ),
+      HaneulError::InvalidArgument { function, value } => write!(
+        f,
+        "{} 함수에 {} 값 {:?}을(를) 넘길 수 없습니다.",
+        function,
+        value.type_name(),
+        value
+      ),
+      HaneulError::DomainError { function, value } => write!(
+        f,
+        "{} 함수는 {:?}에 대해 정의되지 않습니다.",
+        function, value
+      ),
+      HaneulError::IntegerOverflow { function } => {
+        write!(f, "{} 함수의 결과가 정수 범위를 벗어났습니다.", function)
       }
-      HaneulError::UnboundJosa { josa } => {
-        write!(f, "이 함수에서 조사 '{}'를 찾을 수 없습니다.", josa)
+      HaneulError::ParseFailed { expected, text } => {
+        write!(f, "'{}'을(를) {}(으)로 해석할 수 없습니다.", text, expected)
+      }
+      HaneulError::InvalidFormat { template, reason } => {
+        write!(
+          f,
+          "형식 문자열 '{}'을(를) 채울 수 없습니다: {}",
+          template, reason
+        )
+      }
+      HaneulError::PermissionDenied { access, path } => {
+        write!(f, "'{}'에 대한 {} 권한이 없습니다.", path, access)
+      }
+      HaneulError::FileFailed { path, reason } => {
+        write!(f, "'{}'을(를) 다룰 수 없습니다: {}", path, reason)
+      }
+      HaneulError::ModuleNotFound { module } => {
+        write!(f, "모듈 '{}'을(를) 찾을 수 없습니다.", module)
+      }
+      HaneulError::ImportCycle { cycle } => {
+        write!(f, "모듈을 순환하여 가져옵니다: {}", cycle.join(" → "))
+      }
+      HaneulError::MissingExport { module, name } => {
+        write!(
+          f,
+          "모듈 '{}'은(는) '{}'을(를) 내보내지 않습니다.",
+          module, name
+        )
+      }
+      HaneulError::ModuleFailed { module, reason } => {
+        write!(f, "모듈 '{}'을(를) 불러올 수 없습니다: {}", module, reason)
       }
     }
   }
 }
 
 impl error::Error for HaneulError {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn signature(josa_list: Vec<&str>, applied: Vec<bool>) -> JosaSignature {
+    JosaSignature {
+      josa_list: josa_list.into_iter().map(String::from).collect(),
+      applied,
+      variadic: false,
+    }
+  }
+
+  #[test]
+  fn suggest_closest_missing_josa() {
+    let sig = signature(vec!["을", "에"], vec![false, false]);
+    assert_eq!(sig.suggest("를"), Some(String::from("을")));
+    assert_eq!(sig.suggest("에서"), Some(String::from("에")));
+    assert_eq!(sig.suggest("으로부터"), None);
+
+    let sig = signature(vec!["을", "에"], vec![true, false]);
+    assert_eq!(sig.suggest("를"), None);
+    assert_eq!(sig.missing(), vec!["에"]);
+  }
+
+  #[test]
+  fn find_missing_slots_of_repeated_josa() {
+    let sig = signature(vec!["을", "에", "을"], vec![true, false, false]);
+    assert_eq!(sig.applied(), vec!["을"]);
+    assert_eq!(sig.missing(), vec!["에", "을"]);
+    assert_eq!(sig.suggest("를"), Some(String::from("을")));
+  }
+
+  #[test]
+  fn display_unbound_josa() {
+    let sig = signature(vec!["을", "에"], vec![false, true]);
+    let suggestion = sig.suggest("를");
+    let err = HaneulError::UnboundJosa {
+      josa: String::from("를"),
+      signature: sig,
+      suggestion,
+    };
+
+    assert_eq!(
+      err.to_string(),
+      "이 함수에서 조사 '를'를 찾을 수 없습니다.
+  함수의 조사 : 을, 에
+  이미 적용된 조사 : 에
+  아직 적용되지 않은 조사 : 을
+  혹시 조사 '을'를 사용하려고 하셨나요?"
+    );
+  }
+}
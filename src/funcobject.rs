@@ -1,20 +1,65 @@
 use std::fmt;
+use std::mem;
+use std::rc::Rc;
 
-use crate::constant::Constant;
+use crate::constant::{Constant, SizeCounter};
+use crate::error::HaneulError;
+use crate::inline_cache::InlineCaches;
 use crate::instruction::Instruction;
+use crate::io::Io;
 
+/// 내장 함수는 가상 머신의 입출력과 인수들을 받습니다.
+pub type NativeFunction = fn(&mut Io, Vec<Constant>) -> Result<Constant, HaneulError>;
+
+/// 코드 객체의 명령어와 상수 테이블은 여러 값이 공유하므로, 함수 값을 복사해도 다시 복사되지 않습니다.
+/// 인라인 캐시는 실행 중에만 쓰는 정보이므로 비교하거나 출력할 때 무시합니다.
 #[derive(Clone)]
 pub enum FuncObject {
   CodeObject {
-    code: Vec<Instruction>,
-    const_table: Vec<Constant>,
-    free_vars: Vec<Constant>,
+    code: Rc<Vec<Instruction>>,
+    const_table: Rc<Vec<Constant>>,
+    free_vars: Rc<Vec<Constant>>,
+    caches: Rc<InlineCaches>,
   },
   NativeFunc {
-    function: fn(Vec<Constant>) -> Constant,
+    name: &'static str,
+    function: NativeFunction,
   },
 }
 
+impl FuncObject {
+  /// 코드 객체의 명령어, 상수 테이블, 자유 변수가 차지하는 메모리의 대략적인 크기(바이트)입니다.
+  pub fn approx_size(&self, counter: &mut SizeCounter) -> usize {
+    match self {
+      FuncObject::CodeObject {
+        code,
+        const_table,
+        free_vars,
+        ..
+      } => {
+        let mut size = 0;
+        if counter.visit(code) {
+          size += code.len() * mem::size_of::<Instruction>();
+        }
+        if counter.visit(const_table) {
+          size += const_table
+            .iter()
+            .map(|x| x.approx_size(counter))
+            .sum::<usize>();
+        }
+        if counter.visit(free_vars) {
+          size += free_vars
+            .iter()
+            .map(|x| x.approx_size(counter))
+            .sum::<usize>();
+        }
+        size
+      }
+      FuncObject::NativeFunc { .. } => 0,
+    }
+  }
+}
+
 impl fmt::Debug for FuncObject {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
@@ -22,13 +67,16 @@ impl fmt::Debug for FuncObject {
         code,
         const_table,
         free_vars,
+        ..
       } => f
         .debug_struct("CodeObject")
         .field("code", &code)
         .field("const_table", &const_table)
         .field("free_vars", &free_vars)
         .finish(),
-      FuncObject::NativeFunc { .. } => f.debug_struct("NativeFunc").finish(),
+      FuncObject::NativeFunc { name, .. } => {
+        f.debug_struct("NativeFunc").field("name", name).finish()
+      }
     }
   }
 }
@@ -41,11 +89,13 @@ impl PartialEq for FuncObject {
           code: code1,
           const_table: const_table1,
           free_vars: free_vars1,
+          ..
         },
         FuncObject::CodeObject {
           code: code2,
           const_table: const_table2,
           free_vars: free_vars2,
+          ..
         },
       ) => (code1 == code2) && (const_table1 == const_table2) && (free_vars1 == free_vars2),
       _ => false,
@@ -0,0 +1,307 @@
+const SYLLABLE_BASE: u32 = 0xAC00;
+const SYLLABLE_COUNT: u32 = 11172;
+const JUNG_COUNT: u32 = 21;
+const JONG_COUNT: u32 = 28;
+
+const CHO_LIST: [char; 19] = [
+  'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+  'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+const JUNG_LIST: [char; 21] = [
+  'ㅏ', 'ㅐ', 'ㅑ', 'ㅒ', 'ㅓ', 'ㅔ', 'ㅕ', 'ㅖ', 'ㅗ', 'ㅘ', 'ㅙ', 'ㅚ', 'ㅛ', 'ㅜ', 'ㅝ', 'ㅞ',
+  'ㅟ', 'ㅠ', 'ㅡ', 'ㅢ', 'ㅣ',
+];
+
+const JONG_LIST: [char; 27] = [
+  'ㄱ', 'ㄲ', 'ㄳ', 'ㄴ', 'ㄵ', 'ㄶ', 'ㄷ', 'ㄹ', 'ㄺ', 'ㄻ', 'ㄼ', 'ㄽ', 'ㄾ', 'ㄿ', 'ㅀ', 'ㅁ',
+  'ㅂ', 'ㅄ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+/// 한글 음절을 초성, 중성, 종성(없으면 `None`)의 호환용 자모로 분해합니다.
+pub fn decompose(c: char) -> Option<(char, char, Option<char>)> {
+  let code = (c as u32).checked_sub(SYLLABLE_BASE)?;
+  if code >= SYLLABLE_COUNT {
+    return None;
+  }
+
+  let cho = code / (JUNG_COUNT * JONG_COUNT);
+  let jung = (code % (JUNG_COUNT * JONG_COUNT)) / JONG_COUNT;
+  let jong = code % JONG_COUNT;
+
+  Some((
+    CHO_LIST[cho as usize],
+    JUNG_LIST[jung as usize],
+    if jong == 0 {
+      None
+    } else {
+      Some(JONG_LIST[jong as usize - 1])
+    },
+  ))
+}
+
+/// 호환용 자모인 초성, 중성, 종성을 한글 음절로 합칩니다. 합칠 수 없는 자모가 있으면 `None`입니다.
+pub fn compose(cho: char, jung: char, jong: Option<char>) -> Option<char> {
+  let cho = CHO_LIST.iter().position(|x| *x == cho)? as u32;
+  let jung = JUNG_LIST.iter().position(|x| *x == jung)? as u32;
+  let jong = match jong {
+    Some(jong) => JONG_LIST.iter().position(|x| *x == jong)? as u32 + 1,
+    None => 0,
+  };
+
+  std::char::from_u32(SYLLABLE_BASE + (cho * JUNG_COUNT + jung) * JONG_COUNT + jong)
+}
+
+/// 한글 음절에 받침이 있는지 알려줍니다. 한글 음절이 아니면 `None`입니다.
+pub fn has_final_consonant(c: char) -> Option<bool> {
+  decompose(c).map(|(_, _, jong)| jong.is_some())
+}
+
+/// 한글 음절을 초성으로 바꿉니다. 한글 음절이 아닌 문자는 그대로 둡니다.
+pub fn initials(text: &str) -> String {
+  text
+    .chars()
+    .map(|c| decompose(c).map_or(c, |(cho, _, _)| cho))
+    .collect()
+}
+
+/// 문자열의 한글 음절을 자모 단위로 풀어 씁니다. 한글 음절이 아닌 문자는 그대로 둡니다.
+pub fn to_jamo(text: &str) -> Vec<char> {
+  let mut result = Vec::new();
+  for c in text.chars() {
+    match decompose(c) {
+      Some((cho, jung, jong)) => {
+        result.push(cho);
+        result.push(jung);
+        result.extend(jong);
+      }
+      None => result.push(c),
+    }
+  }
+  result
+}
+
+/// 두 문자열을 자모 단위로 비교한 편집 거리입니다.
+pub fn jamo_distance(a: &str, b: &str) -> usize {
+  let a = to_jamo(a);
+  let b = to_jamo(b);
+
+  let mut prev: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.iter().enumerate() {
+    let mut current = vec![i + 1];
+    for (j, cb) in b.iter().enumerate() {
+      let cost = if ca == cb { 0 } else { 1 };
+      current.push((prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1));
+    }
+    prev = current;
+  }
+
+  prev[b.len()]
+}
+
+/// 받침이 있을 때와 없을 때 쓰는 조사의 짝입니다.
+const JOSA_PAIRS: [(&str, &str); 5] = [
+  ("을", "를"),
+  ("이", "가"),
+  ("은", "는"),
+  ("과", "와"),
+  ("으로", "로"),
+];
+
+/// 조사를 고를 때 보는 앞 단어의 끝소리입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FinalSound {
+  Vowel,
+  Rieul,
+  Consonant,
+}
+
+/// 숫자는 한자어로 읽은 소리로 봅니다. 끝의 0은 십, 백, 천, 만처럼 자릿수로 읽습니다.
+fn number_final_sound(digits: &str) -> FinalSound {
+  let zeros = digits.chars().rev().take_while(|c| *c == '0').count();
+  if zeros == digits.len() {
+    return FinalSound::Consonant;
+  }
+
+  match zeros {
+    0 => match digits.chars().last() {
+      Some('1') | Some('7') | Some('8') => FinalSound::Rieul,
+      Some('3') | Some('6') => FinalSound::Consonant,
+      _ => FinalSound::Vowel,
+    },
+    12..=15 => FinalSound::Vowel,
+    _ => FinalSound::Consonant,
+  }
+}
+
+/// 로마자는 대강 읽은 소리로 봅니다. 모두 대문자면 약어로 보고 마지막 글자의 이름으로 읽습니다.
+fn latin_final_sound(letters: &str) -> FinalSound {
+  let lower = letters.to_ascii_lowercase();
+  let mut rev = lower.chars().rev();
+  let last = rev.next();
+  let before = rev.next();
+
+  if letters.chars().all(|c| c.is_ascii_uppercase()) {
+    return match last {
+      Some('l') | Some('r') => FinalSound::Rieul,
+      Some('m') | Some('n') => FinalSound::Consonant,
+      _ => FinalSound::Vowel,
+    };
+  }
+
+  let after_vowel = matches!(
+    before,
+    Some('a') | Some('e') | Some('i') | Some('o') | Some('u')
+  );
+  match last {
+    Some('l') => FinalSound::Rieul,
+    Some('m') | Some('n') => FinalSound::Consonant,
+    Some('g') if before == Some('n') => FinalSound::Consonant,
+    Some('b') | Some('c') | Some('k') | Some('p') | Some('t') if after_vowel => {
+      FinalSound::Consonant
+    }
+    _ => FinalSound::Vowel,
+  }
+}
+
+/// 끝의 문장 부호와 공백은 건너뛰고 단어의 끝소리를 봅니다. 알 수 없으면 `None`입니다.
+fn final_sound(word: &str) -> Option<FinalSound> {
+  let word = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+  let last = word.chars().last()?;
+
+  if let Some((_, _, jong)) = decompose(last) {
+    return Some(match jong {
+      None => FinalSound::Vowel,
+      Some('ㄹ') => FinalSound::Rieul,
+      Some(_) => FinalSound::Consonant,
+    });
+  }
+
+  if last.is_ascii_digit() {
+    // `1,000`처럼 자릿수를 나누는 쉼표는 빼고 읽습니다.
+    let number = trailing(word, |c| c.is_ascii_digit() || *c == ',');
+    let digits: String = number
+      .trim_start_matches(',')
+      .chars()
+      .filter(|c| *c != ',')
+      .collect();
+    Some(number_final_sound(&digits))
+  } else if last.is_ascii_alphabetic() {
+    Some(latin_final_sound(trailing(word, char::is_ascii_alphabetic)))
+  } else {
+    None
+  }
+}
+
+/// 문자열 끝에서 `pred`를 만족하는 문자가 이어지는 부분입니다.
+fn trailing(word: &str, pred: fn(&char) -> bool) -> &str {
+  let length: usize = word
+    .chars()
+    .rev()
+    .take_while(pred)
+    .map(char::len_utf8)
+    .sum();
+  &word[word.len() - length..]
+}
+
+/// 단어 뒤에 알맞은 조사를 붙입니다. 조사는 `을`, `를`, `을/를`처럼 짝 가운데 어느 쪽으로 주어도 됩니다.
+/// 끝소리를 알 수 없으면 `을(를)`이나 `(으)로`처럼 둘 다 적고, 모르는 조사면 `None`입니다.
+pub fn attach_josa(word: &str, josa: &str) -> Option<String> {
+  let &(consonant, vowel) = JOSA_PAIRS.iter().find(|(consonant, vowel)| {
+    josa == *consonant || josa == *vowel || josa == format!("{}/{}", consonant, vowel)
+  })?;
+
+  let josa = match final_sound(word) {
+    Some(FinalSound::Vowel) => vowel.to_string(),
+    Some(FinalSound::Rieul) if vowel == "로" => vowel.to_string(),
+    Some(_) => consonant.to_string(),
+    None if vowel == "로" => String::from("(으)로"),
+    None => format!("{}({})", consonant, vowel),
+  };
+  Some(format!("{}{}", word, josa))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decompose_syllable() {
+    assert_eq!(decompose('가'), Some(('ㄱ', 'ㅏ', None)));
+    assert_eq!(decompose('힣'), Some(('ㅎ', 'ㅣ', Some('ㅎ'))));
+    assert_eq!(decompose('을'), Some(('ㅇ', 'ㅡ', Some('ㄹ'))));
+    assert_eq!(decompose('a'), None);
+    assert_eq!(decompose('ㄱ'), None);
+  }
+
+  #[test]
+  fn compose_every_syllable() {
+    for code in SYLLABLE_BASE..SYLLABLE_BASE + SYLLABLE_COUNT {
+      let c = std::char::from_u32(code).unwrap();
+      let (cho, jung, jong) = decompose(c).unwrap();
+      assert_eq!(compose(cho, jung, jong), Some(c));
+    }
+
+    assert_eq!(compose('ㅎ', 'ㅏ', Some('ㄴ')), Some('한'));
+    assert_eq!(compose('ㄳ', 'ㅏ', None), None);
+    assert_eq!(compose('ㄱ', 'ㄱ', None), None);
+    assert_eq!(compose('ㄸ', 'ㅏ', Some('ㄸ')), None);
+  }
+
+  #[test]
+  fn final_consonant_and_initials() {
+    assert_eq!(has_final_consonant('각'), Some(true));
+    assert_eq!(has_final_consonant('가'), Some(false));
+    assert_eq!(has_final_consonant('A'), None);
+    assert_eq!(initials("하늘 언어 3"), "ㅎㄴ ㅇㅇ 3");
+  }
+
+  #[test]
+  fn attach_josa_by_final_sound() {
+    assert_eq!(attach_josa("사과", "을").unwrap(), "사과를");
+    assert_eq!(attach_josa("수박", "를").unwrap(), "수박을");
+    assert_eq!(attach_josa("하늘", "이/가").unwrap(), "하늘이");
+    assert_eq!(attach_josa("하늘", "으로").unwrap(), "하늘로");
+    assert_eq!(attach_josa("닭", "로").unwrap(), "닭으로");
+    assert_eq!(attach_josa("바다", "은/는").unwrap(), "바다는");
+    assert_eq!(attach_josa("친구", "과").unwrap(), "친구와");
+    assert_eq!(attach_josa("'책'", "을").unwrap(), "'책'을");
+
+    assert_eq!(attach_josa("1", "으로").unwrap(), "1로");
+    assert_eq!(attach_josa("3", "이").unwrap(), "3이");
+    assert_eq!(attach_josa("2", "이").unwrap(), "2가");
+    assert_eq!(attach_josa("10", "를").unwrap(), "10을");
+    assert_eq!(attach_josa("0", "는").unwrap(), "0은");
+    assert_eq!(
+      attach_josa("1000000000000", "가").unwrap(),
+      "1000000000000가"
+    );
+    assert_eq!(
+      attach_josa("1,000,000,000,000", "가").unwrap(),
+      "1,000,000,000,000가"
+    );
+    assert_eq!(attach_josa("1,000", "이").unwrap(), "1,000이");
+    assert_eq!(attach_josa("12,345", "이").unwrap(), "12,345가");
+    assert_eq!(attach_josa("가,1", "을").unwrap(), "가,1을");
+    assert_eq!(attach_josa("버전 2.0", "을").unwrap(), "버전 2.0을");
+
+    assert_eq!(attach_josa("SQL", "로").unwrap(), "SQL로");
+    assert_eq!(attach_josa("API", "을").unwrap(), "API를");
+    assert_eq!(attach_josa("Python", "이").unwrap(), "Python이");
+    assert_eq!(attach_josa("Rust", "는").unwrap(), "Rust는");
+    assert_eq!(attach_josa("book", "을").unwrap(), "book을");
+    assert_eq!(attach_josa("Google", "로").unwrap(), "Google로");
+
+    assert_eq!(attach_josa("?", "을").unwrap(), "?을(를)");
+    assert_eq!(attach_josa("", "로").unwrap(), "(으)로");
+    assert_eq!(attach_josa("사과", "에서"), None);
+  }
+
+  #[test]
+  fn distance() {
+    assert_eq!(jamo_distance("을", "을"), 0);
+    assert_eq!(jamo_distance("을", "를"), 1);
+    assert_eq!(jamo_distance("을", "에"), 2);
+    assert_eq!(jamo_distance("으로", "로"), 2);
+  }
+}
@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::josa::{JosaId, JosaMap};
+
+/// 호출할 때 넘긴 인수 하나가 들어가는 곳입니다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgPosition {
+  Param(usize),
+  Rest,
+}
+
+/// 호출 명령어가 마지막으로 끝까지 호출한 함수의 모양과, 넘긴 인수들이 들어간 자리입니다.
+/// 조사 목록과 이미 값이 들어간 자리가 같은 함수라면 인수가 들어갈 자리도 같으므로, 인수의 값은 남기지 않습니다.
+#[derive(Debug)]
+pub struct CallCache {
+  /// 함수의 조사마다 호출하기 전에 값이 들어 있었는지입니다.
+  pub slots: Vec<(JosaId, bool)>,
+  pub variadic: bool,
+  pub positions: Vec<ArgPosition>,
+}
+
+impl CallCache {
+  pub fn matches(&self, josa_map: &JosaMap, variadic: bool) -> bool {
+    self.variadic == variadic
+      && self.slots.len() == josa_map.len()
+      && self
+        .slots
+        .iter()
+        .zip(josa_map.iter())
+        .all(|((josa, applied), (key, value))| josa == key && *applied == value.is_some())
+  }
+}
+
+/// 코드 객체의 명령어마다 하나씩 있는 인라인 캐시입니다.
+/// 같은 코드 객체에서 만들어진 함수 값들은 캐시를 함께 씁니다.
+#[derive(Debug, Default)]
+pub struct InlineCaches {
+  calls: RefCell<Vec<Option<Rc<CallCache>>>>,
+}
+
+impl InlineCaches {
+  pub fn call(&self, ip: usize) -> Option<Rc<CallCache>> {
+    self.calls.borrow().get(ip).cloned().flatten()
+  }
+
+  pub fn set_call(&self, ip: usize, cache: CallCache) {
+    let mut calls = self.calls.borrow_mut();
+    if calls.len() <= ip {
+      calls.resize(ip + 1, None);
+    }
+    calls[ip] = Some(Rc::new(cache));
+  }
+}
@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::rc::Rc;
+
+use crate::error::HaneulError;
+use crate::permission::Permissions;
+
+/// 프로그램이 출력하는 내용을 받는 곳입니다.
+pub trait Output {
+  fn write_str(&mut self, text: &str) -> io::Result<()>;
+}
+
+/// 표준 출력으로 내보냅니다.
+#[derive(Debug, Default)]
+pub struct StdoutOutput;
+
+impl Output for StdoutOutput {
+  fn write_str(&mut self, text: &str) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    handle.write_all(text.as_bytes())?;
+    handle.flush()
+  }
+}
+
+/// 출력한 내용을 메모리에 모아 둡니다. 복제한 값들은 같은 버퍼를 공유하므로,
+/// 가상 머신에 하나를 넘겨주고 남은 하나로 내용을 읽으면 됩니다.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryOutput {
+  buffer: Rc<RefCell<String>>,
+}
+
+impl MemoryOutput {
+  pub fn new() -> MemoryOutput {
+    Default::default()
+  }
+
+  pub fn contents(&self) -> String {
+    self.buffer.borrow().clone()
+  }
+
+  /// 지금까지 모인 내용을 돌려주고 버퍼를 비웁니다.
+  pub fn take(&self) -> String {
+    self.buffer.replace(String::new())
+  }
+}
+
+impl Output for MemoryOutput {
+  fn write_str(&mut self, text: &str) -> io::Result<()> {
+    self.buffer.borrow_mut().push_str(text);
+    Ok(())
+  }
+}
+
+/// 사용자가 넘겨준 `Write`로 내보냅니다.
+pub struct WriterOutput<W>(pub W);
+
+impl<W: Write> Output for WriterOutput<W> {
+  fn write_str(&mut self, text: &str) -> io::Result<()> {
+    self.0.write_all(text.as_bytes())?;
+    self.0.flush()
+  }
+}
+
+/// 가상 머신이 가지고 있다가 내장 함수를 호출할 때 넘겨주는 입출력입니다.
+/// 입력은 `BufRead`라면 무엇이든 될 수 있으므로, 시험할 때는 `Cursor`로 미리 정한 입력을 넣으면 됩니다.
+/// 파일을 다루는 내장 함수는 `permissions`가 허락한 경로에만 접근합니다.
+pub struct Io {
+  output: Box<dyn Output>,
+  input: Box<dyn BufRead>,
+  permissions: Permissions,
+}
+
+impl Default for Io {
+  fn default() -> Io {
+    Io::new(
+      Box::new(StdoutOutput),
+      Box::new(BufReader::new(io::stdin())),
+    )
+  }
+}
+
+fn input_failed(err: io::Error) -> HaneulError {
+  HaneulError::InputFailed {
+    reason: err.to_string(),
+  }
+}
+
+impl Io {
+  pub fn new(output: Box<dyn Output>, input: Box<dyn BufRead>) -> Io {
+    Io {
+      output,
+      input,
+      permissions: Permissions::new(),
+    }
+  }
+
+  pub fn set_permissions(&mut self, permissions: Permissions) {
+    self.permissions = permissions;
+  }
+
+  pub fn permissions(&self) -> &Permissions {
+    &self.permissions
+  }
+
+  pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+    self.input = input;
+  }
+
+  pub fn set_output(&mut self, output: Box<dyn Output>) {
+    self.output = output;
+  }
+
+  pub fn output(&mut self) -> &mut dyn Output {
+    self.output.as_mut()
+  }
+
+  pub fn write(&mut self, text: &str) -> Result<(), HaneulError> {
+    self
+      .output
+      .write_str(text)
+      .map_err(|err| HaneulError::OutputFailed {
+        reason: err.to_string(),
+      })
+  }
+
+  /// 줄바꿈 문자를 뺀 한 줄을 읽습니다. 입력이 끝났으면 `None`입니다.
+  pub fn read_line(&mut self) -> Result<Option<String>, HaneulError> {
+    let mut line = String::new();
+    if self.input.read_line(&mut line).map_err(input_failed)? == 0 {
+      return Ok(None);
+    }
+
+    if line.ends_with('\n') {
+      line.pop();
+      if line.ends_with('\r') {
+        line.pop();
+      }
+    }
+    Ok(Some(line))
+  }
+
+  /// 문자 하나를 읽습니다. 줄바꿈 문자도 그대로 읽습니다. 입력이 끝났으면 `None`입니다.
+  pub fn read_char(&mut self) -> Result<Option<char>, HaneulError> {
+    let mut bytes = [0; 4];
+    if self.input.read(&mut bytes[..1]).map_err(input_failed)? == 0 {
+      return Ok(None);
+    }
+
+    let length = match bytes[0] {
+      head if head < 0x80 => 1,
+      head if head < 0xE0 => 2,
+      head if head < 0xF0 => 3,
+      _ => 4,
+    };
+    self
+      .input
+      .read_exact(&mut bytes[1..length])
+      .map_err(input_failed)?;
+
+    match std::str::from_utf8(&bytes[..length]) {
+      Ok(text) => Ok(text.chars().next()),
+      Err(err) => Err(HaneulError::InputFailed {
+        reason: err.to_string(),
+      }),
+    }
+  }
+
+  pub fn at_end(&mut self) -> Result<bool, HaneulError> {
+    Ok(self.input.fill_buf().map_err(input_failed)?.is_empty())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::builtin::get_builtin;
+  use crate::constant::Constant;
+  use crate::instruction::Instruction;
+  use crate::josa::{JosaTable, BLANK};
+  use crate::machine::{Machine, StackFrame};
+  use crate::opcode::Opcode;
+
+  #[test]
+  fn capture_output_in_memory() {
+    let mut josa_table = JosaTable::new();
+    let names = vec![String::from("출력")];
+    let builtin = get_builtin(&mut josa_table, &names);
+    let eul = josa_table.intern("을");
+
+    let code = vec![
+      Opcode::Push(1),
+      Opcode::Push(0),
+      Opcode::LoadGlobal(0),
+      Opcode::Call(vec![eul, BLANK]),
+    ]
+    .into_iter()
+    .map(|opcode| Instruction {
+      line_number: 1,
+      opcode,
+    })
+    .collect();
+
+    let output = MemoryOutput::new();
+    let mut machine = Machine::new(builtin, names, josa_table);
+    machine.set_output(Box::new(output.clone()));
+    assert!(machine
+      .run(StackFrame::new(
+        code,
+        vec![Constant::Integer(1), Constant::Boolean(true)]
+      ))
+      .is_ok());
+
+    assert_eq!(output.contents(), "1 참\n");
+    assert_eq!(output.take(), "1 참\n");
+    assert_eq!(output.contents(), "");
+  }
+}
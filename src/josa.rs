@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::slice;
+use std::vec;
+
+use crate::constant::Constant;
+
+/// 프로그램의 조사 테이블에서 조사 하나를 가리키는 번호입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JosaId(pub u32);
+
+/// 첫 번째 빈 자리에 인수를 넣는 `_` 조사는 항상 0번입니다.
+pub const BLANK: JosaId = JosaId(0);
+
+/// 프로그램을 불러올 때 조사 문자열을 번호로 바꿔 두는 테이블입니다.
+/// 호출할 때는 번호끼리만 비교하고, 에러 메시지를 만들 때 다시 문자열로 바꿉니다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JosaTable {
+  names: Vec<String>,
+  ids: HashMap<String, JosaId>,
+}
+
+impl Default for JosaTable {
+  fn default() -> JosaTable {
+    let mut table = JosaTable {
+      names: Vec::new(),
+      ids: HashMap::new(),
+    };
+    table.intern("_");
+    table
+  }
+}
+
+impl JosaTable {
+  pub fn new() -> JosaTable {
+    Default::default()
+  }
+
+  pub fn intern(&mut self, josa: &str) -> JosaId {
+    if let Some(id) = self.ids.get(josa) {
+      return *id;
+    }
+
+    let id = JosaId(self.names.len() as u32);
+    self.names.push(josa.to_string());
+    self.ids.insert(josa.to_string(), id);
+    id
+  }
+
+  pub fn get(&self, josa: &str) -> Option<JosaId> {
+    self.ids.get(josa).copied()
+  }
+
+  pub fn name(&self, id: JosaId) -> &str {
+    &self.names[id.0 as usize]
+  }
+
+  pub fn names(&self) -> &[String] {
+    &self.names
+  }
+}
+
+/// 함수의 조사마다 하나씩 있는 인수 자리입니다. 조사 번호는 조사 테이블 안에서 촘촘하므로,
+/// 조사 번호를 첨자로 하는 `positions`에서 자리를 바로 찾습니다.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JosaMap {
+  slots: Vec<(JosaId, Option<Constant>)>,
+  /// 조사 번호마다 그 조사의 자리 순서입니다. 같은 조사가 여러 번 있으면 첫 번째 자리입니다.
+  positions: Vec<Option<usize>>,
+}
+
+impl JosaMap {
+  /// 모든 자리가 비어 있는 조사 목록을 만듭니다.
+  pub fn new(josa_list: impl IntoIterator<Item = JosaId>) -> JosaMap {
+    let slots: Vec<(JosaId, Option<Constant>)> =
+      josa_list.into_iter().map(|josa| (josa, None)).collect();
+
+    let mut positions = Vec::new();
+    for (index, (josa, _)) in slots.iter().enumerate() {
+      let id = josa.0 as usize;
+      if positions.len() <= id {
+        positions.resize(id + 1, None);
+      }
+      positions[id].get_or_insert(index);
+    }
+
+    JosaMap { slots, positions }
+  }
+
+  pub fn len(&self) -> usize {
+    self.slots.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.slots.is_empty()
+  }
+
+  pub fn get(&self, josa: JosaId) -> Option<&Option<Constant>> {
+    let index = self.position(josa)?;
+    Some(&self.slots[index].1)
+  }
+
+  pub fn get_mut(&mut self, josa: JosaId) -> Option<&mut Option<Constant>> {
+    let index = self.position(josa)?;
+    Some(&mut self.slots[index].1)
+  }
+
+  /// 조사에 해당하는 자리의 순서입니다.
+  pub fn position(&self, josa: JosaId) -> Option<usize> {
+    self.positions.get(josa.0 as usize).copied().flatten()
+  }
+
+  pub fn value_mut(&mut self, index: usize) -> &mut Option<Constant> {
+    &mut self.slots[index].1
+  }
+
+  pub fn keys(&self) -> impl Iterator<Item = JosaId> + '_ {
+    self.slots.iter().map(|x| x.0)
+  }
+
+  pub fn values(&self) -> impl Iterator<Item = &Option<Constant>> {
+    self.slots.iter().map(|x| &x.1)
+  }
+
+  pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Option<Constant>> {
+    self.slots.iter_mut().map(|x| &mut x.1)
+  }
+
+  pub fn iter(&self) -> slice::Iter<'_, (JosaId, Option<Constant>)> {
+    self.slots.iter()
+  }
+}
+
+impl IntoIterator for JosaMap {
+  type Item = (JosaId, Option<Constant>);
+  type IntoIter = vec::IntoIter<(JosaId, Option<Constant>)>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.slots.into_iter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn intern_josa() {
+    let mut table = JosaTable::new();
+    assert_eq!(table.get("_"), Some(BLANK));
+
+    let eul = table.intern("을");
+    let e = table.intern("에");
+    assert_eq!(table.intern("을"), eul);
+    assert_ne!(eul, e);
+    assert_eq!(table.name(e), "에");
+    assert_eq!(table.get("로"), None);
+  }
+
+  #[test]
+  fn find_slots_by_josa_id() {
+    let mut map = JosaMap::new(vec![JosaId(5), JosaId(2), JosaId(5)]);
+    assert_eq!(map.position(JosaId(2)), Some(1));
+    assert_eq!(map.position(JosaId(5)), Some(0));
+    assert_eq!(map.position(JosaId(3)), None);
+    assert_eq!(map.position(JosaId(100)), None);
+
+    *map.get_mut(JosaId(2)).unwrap() = Some(Constant::Integer(1));
+    assert_eq!(map.get(JosaId(2)), Some(&Some(Constant::Integer(1))));
+    assert_eq!(map.values().nth(1), Some(&Some(Constant::Integer(1))));
+    assert_eq!(map.get(JosaId(4)), None);
+  }
+}
@@ -0,0 +1,24 @@
+pub mod builtin;
+pub mod constant;
+pub mod coverage;
+pub mod dap;
+pub mod debugger;
+pub mod encoder;
+pub mod error;
+pub mod funcobject;
+pub mod hangul;
+pub mod inline_cache;
+pub mod instruction;
+pub mod io;
+pub mod josa;
+pub mod linker;
+pub mod machine;
+pub mod module;
+pub mod opcode;
+pub mod optimizer;
+pub mod parser;
+pub mod permission;
+pub mod profiler;
+pub mod program;
+mod relocation;
+pub mod snapshot;
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+use crate::constant::Constant;
+use crate::funcobject::FuncObject;
+use crate::instruction::Instruction;
+use crate::josa::JosaTable;
+use crate::opcode::Opcode;
+use crate::optimizer::same_constant;
+use crate::program::{Import, Program};
+use crate::relocation::Relocation;
+
+#[derive(Debug)]
+pub enum LinkError {
+  ConflictingDefinition {
+    name: String,
+    modules: (String, String),
+  },
+  ImportCycle {
+    cycle: Vec<String>,
+  },
+  MissingExport {
+    module: String,
+    name: String,
+  },
+}
+
+impl fmt::Display for LinkError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      LinkError::ConflictingDefinition {
+        name,
+        modules: (first, second),
+      } => write!(
+        f,
+        "전역 변수 '{}'을(를) 모듈 '{}'과(와) '{}'에서 모두 정의합니다.",
+        name, first, second
+      ),
+      LinkError::ImportCycle { cycle } => {
+        write!(f, "모듈을 순환하여 가져옵니다: {}", cycle.join(" → "))
+      }
+      LinkError::MissingExport { module, name } => {
+        write!(
+          f,
+          "모듈 '{}'은(는) '{}'을(를) 내보내지 않습니다.",
+          module, name
+        )
+      }
+    }
+  }
+}
+
+impl error::Error for LinkError {}
+
+/// 링크할 프로그램과 그 모듈 이름입니다.
+pub struct LinkUnit {
+  pub name: String,
+  pub program: Program,
+}
+
+/// 여러 프로그램을 하나로 합칩니다. 같은 이름의 전역 변수는 하나로 합치고, 최상위 상수 테이블의 같은 상수도 하나로 합칩니다.
+/// 다만 한 프로그램만 쓰는 전역 변수는 다른 프로그램에도 같은 이름이 있으면 `모듈::이름`으로 이름을 바꿔 따로 둡니다.
+/// 최상위 코드는 가져오는 모듈이 먼저 실행되도록 차례를 정해 이어 붙이며, 함께 링크한 모듈에서 가져오는 것은
+/// 결과에서 빠지고 나머지 모듈에서 가져오는 것만 남습니다.
+pub fn link(units: Vec<LinkUnit>) -> Result<Program, LinkError> {
+  let order = link_order(&units)?;
+  check_imports(&units)?;
+  check_definitions(&units)?;
+
+  let mut linked = Program {
+    const_table: Vec::new(),
+    code: Vec::new(),
+    global_var_names: Vec::new(),
+    josa_table: JosaTable::new(),
+    imports: Vec::new(),
+    exports: Vec::new(),
+  };
+
+  let mut mentions: HashMap<&str, usize> = HashMap::new();
+  for unit in &units {
+    for name in &unit.program.global_var_names {
+      *mentions.entry(name).or_default() += 1;
+    }
+  }
+
+  for index in order {
+    let LinkUnit {
+      name: module,
+      program,
+    } = &units[index];
+    let private = private_globals(program, &stored_globals(program));
+    let relocation = Relocation {
+      globals: program
+        .global_var_names
+        .iter()
+        .zip(private)
+        .map(|(name, private)| {
+          if private && mentions[name.as_str()] > 1 {
+            add_name(
+              &mut linked.global_var_names,
+              &format!("{}::{}", module, name),
+            )
+          } else {
+            add_name(&mut linked.global_var_names, name)
+          }
+        })
+        .collect(),
+      josa: program
+        .josa_table
+        .names()
+        .iter()
+        .map(|name| linked.josa_table.intern(name))
+        .collect(),
+    };
+    let jump_offset = linked.code.len() as u32;
+
+    let constants: Vec<u32> = program
+      .const_table
+      .iter()
+      .map(|value| add_constant(&mut linked.const_table, relocation.constant(value)))
+      .collect();
+    linked.code.extend(
+      program
+        .code
+        .iter()
+        .map(|inst| relocation.instruction(inst, &constants, jump_offset)),
+    );
+
+    for import in &program.imports {
+      if units.iter().any(|unit| unit.name == import.module) {
+        continue;
+      }
+      match linked
+        .imports
+        .iter_mut()
+        .find(|x| x.module == import.module)
+      {
+        Some(existing) => {
+          for name in &import.names {
+            add_name(&mut existing.names, name);
+          }
+        }
+        None => linked.imports.push(import.clone()),
+      }
+    }
+    for name in &program.exports {
+      add_name(&mut linked.exports, name);
+    }
+  }
+
+  Ok(linked)
+}
+
+fn add_name(names: &mut Vec<String>, name: &str) -> u32 {
+  match names.iter().position(|x| x == name) {
+    Some(index) => index as u32,
+    None => {
+      names.push(name.to_string());
+      (names.len() - 1) as u32
+    }
+  }
+}
+
+/// 함수는 명령어와 상수 테이블이 모두 같을 때만 같은 상수로 봅니다.
+fn add_constant(const_table: &mut Vec<Constant>, value: Constant) -> u32 {
+  let same = |x: &Constant| match (x, &value) {
+    (Constant::Function { .. }, Constant::Function { .. }) => *x == value,
+    (x, value) => same_constant(x, value),
+  };
+
+  match const_table.iter().position(same) {
+    Some(index) => index as u32,
+    None => {
+      const_table.push(value);
+      (const_table.len() - 1) as u32
+    }
+  }
+}
+
+/// 가져오는 모듈이 먼저 오도록 정한 프로그램의 차례입니다. 같은 조건이면 주어진 차례를 따릅니다.
+fn link_order(units: &[LinkUnit]) -> Result<Vec<usize>, LinkError> {
+  fn visit(
+    units: &[LinkUnit],
+    index: usize,
+    visiting: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+  ) -> Result<(), LinkError> {
+    if order.contains(&index) {
+      return Ok(());
+    }
+    if let Some(start) = visiting.iter().position(|x| *x == index) {
+      let mut cycle: Vec<String> = visiting[start..]
+        .iter()
+        .map(|x| units[*x].name.clone())
+        .collect();
+      cycle.push(units[index].name.clone());
+      return Err(LinkError::ImportCycle { cycle });
+    }
+
+    visiting.push(index);
+    for import in &units[index].program.imports {
+      if let Some(dependency) = units.iter().position(|unit| unit.name == import.module) {
+        visit(units, dependency, visiting, order)?;
+      }
+    }
+    visiting.pop();
+    order.push(index);
+    Ok(())
+  }
+
+  let mut order = Vec::new();
+  for index in 0..units.len() {
+    visit(units, index, &mut Vec::new(), &mut order)?;
+  }
+  Ok(order)
+}
+
+/// 함께 링크한 모듈에서 가져오는 이름은 그 모듈이 내보내야 합니다.
+fn check_imports(units: &[LinkUnit]) -> Result<(), LinkError> {
+  for unit in units {
+    for Import { module, names } in &unit.program.imports {
+      if let Some(dependency) = units.iter().find(|unit| unit.name == *module) {
+        if let Some(name) = names
+          .iter()
+          .find(|name| !dependency.program.exports.contains(name))
+        {
+          return Err(LinkError::MissingExport {
+            module: module.clone(),
+            name: name.clone(),
+          });
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
+/// 프로그램이 값을 넣지만 가져오지도 내보내지도 않는 전역 변수는 그 프로그램만 씁니다.
+fn private_globals(program: &Program, stored: &[bool]) -> Vec<bool> {
+  program
+    .global_var_names
+    .iter()
+    .zip(stored)
+    .map(|(name, stored)| {
+      *stored
+        && !program.exports.contains(name)
+        && !program
+          .imports
+          .iter()
+          .any(|import| import.names.contains(name))
+    })
+    .collect()
+}
+
+fn stored_globals(program: &Program) -> Vec<bool> {
+  let mut indices = Vec::new();
+  collect_stores(&program.code, &program.const_table, &mut indices);
+  let mut stored = vec![false; program.global_var_names.len()];
+  for index in indices {
+    stored[index as usize] = true;
+  }
+  stored
+}
+
+/// 여러 프로그램이 함께 쓰는 같은 전역 변수에 값을 넣는 프로그램이 둘 이상이면 충돌입니다.
+fn check_definitions(units: &[LinkUnit]) -> Result<(), LinkError> {
+  let mut defined: HashMap<&str, &str> = HashMap::new();
+  for unit in units {
+    let program = &unit.program;
+    let stored = stored_globals(program);
+    let private = private_globals(program, &stored);
+
+    for (index, name) in program.global_var_names.iter().enumerate() {
+      if !stored[index] || private[index] {
+        continue;
+      }
+      if let Some(first) = defined.insert(name, &unit.name) {
+        return Err(LinkError::ConflictingDefinition {
+          name: name.clone(),
+          modules: (first.to_string(), unit.name.clone()),
+        });
+      }
+    }
+  }
+  Ok(())
+}
+
+fn collect_stores(code: &[Instruction], const_table: &[Constant], indices: &mut Vec<u32>) {
+  for inst in code {
+    if let Opcode::StoreGlobal(index) = inst.opcode {
+      indices.push(index);
+    }
+  }
+  for value in const_table {
+    if let Constant::Function {
+      func_object: FuncObject::CodeObject {
+        code, const_table, ..
+      },
+      ..
+    } = value
+    {
+      collect_stores(code, const_table, indices);
+    }
+  }
+}
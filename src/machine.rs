@@ -1,259 +1,1267 @@
-use crate::constant::Constant;
-use crate::error::HaneulError;
+use std::io::{BufRead, Write};
+use std::mem;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::constant::{Constant, SizeCounter};
+use crate::coverage::Coverage;
+use crate::error::{HaneulError, JosaSignature};
 use crate::funcobject::FuncObject;
+use crate::inline_cache::{ArgPosition, CallCache, InlineCaches};
 use crate::instruction::Instruction;
+use crate::io::{Io, Output};
+use crate::josa::{JosaId, JosaMap, JosaTable, BLANK};
 use crate::opcode::{BinaryOp, Opcode, UnaryOp};
+use crate::permission::Permissions;
+use crate::profiler::Profiler;
+use crate::snapshot::Snapshot;
+
+/// 실행 제한 시간은 명령어를 이만큼 실행할 때마다 한 번씩 확인합니다.
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// 최상위 코드를 가리키는 이름입니다.
+pub(crate) const TOP_LEVEL_LABEL: &str = "(최상위)";
+
+/// `josa_map`의 조사 목록에 `args`를 넣은 조사 목록입니다.
+fn applied_map(josa_map: &JosaMap, args: Vec<Option<Constant>>) -> JosaMap {
+  let mut map = JosaMap::new(josa_map.keys());
+  for (slot, value) in map.values_mut().zip(args) {
+    *slot = value;
+  }
+  map
+}
+
+fn josa_signature(
+  josa_map: &JosaMap,
+  rest_args: &Option<Rc<Vec<Constant>>>,
+  josa_table: &JosaTable,
+) -> JosaSignature {
+  JosaSignature {
+    josa_list: josa_map
+      .keys()
+      .map(|josa| josa_table.name(josa).to_string())
+      .collect(),
+    applied: josa_map.values().map(Option::is_some).collect(),
+    variadic: rest_args.is_some(),
+  }
+}
 
+/// 프로파일러와 커버리지 보고서에서 코드 객체를 가리키는 이름입니다.
+pub(crate) fn function_label(local_names: &[&str], code: &[Instruction]) -> String {
+  let first_line = code.first().map_or(0, |x| x.line_number);
+  format!("함수({}) {}번째 줄", local_names.join(", "), first_line)
+}
+
+/// 한 프로그램이 쓸 수 있는 메모리의 상한입니다. `None`인 항목은 제한하지 않습니다.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MemoryLimits {
+  pub max_stack_size: Option<usize>,
+  pub max_call_depth: Option<usize>,
+  pub max_heap_size: Option<usize>,
+}
+
+#[derive(Clone)]
 pub struct StackFrame {
-  pub code: Vec<Instruction>,
-  pub const_table: Vec<Constant>,
-  pub free_vars: Vec<Constant>,
+  pub code: Rc<Vec<Instruction>>,
+  pub const_table: Rc<Vec<Constant>>,
+  pub free_vars: Rc<Vec<Constant>>,
+  pub caches: Rc<InlineCaches>,
   pub slot_start: usize,
+  pub ip: usize,
+  /// 인수 자리마다의 조사입니다. 가변 인수 함수라면 그 뒤에 나머지 인수 목록이 한 자리 더 있습니다.
+  pub josa_list: Vec<JosaId>,
+  pub variadic: bool,
+}
+
+impl StackFrame {
+  /// 프로그램의 최상위 코드를 실행하는 프레임을 만듭니다.
+  pub fn new(code: Vec<Instruction>, const_table: Vec<Constant>) -> StackFrame {
+    StackFrame {
+      code: Rc::new(code),
+      const_table: Rc::new(const_table),
+      free_vars: Rc::default(),
+      caches: Rc::default(),
+      slot_start: 0,
+      ip: 0,
+      josa_list: Vec::new(),
+      variadic: false,
+    }
+  }
 }
 
 #[derive(Default)]
 pub struct Machine {
   operand_stack: Vec<Constant>,
+  call_stack: Vec<StackFrame>,
   global_vars: Vec<Option<Constant>>,
   global_var_names: Vec<String>,
+  josa_table: JosaTable,
+  fuel: Option<u64>,
+  deadline: Option<Instant>,
+  executed_count: u64,
+  memory_limits: MemoryLimits,
+  /// 힙 크기를 마지막으로 잰 뒤 새로 만든 값의 크기를 더한 어림값입니다. 실제 크기보다 작지 않으므로,
+  /// 이 값이 한도를 넘을 때만 힙 전체를 다시 잽니다.
+  heap_estimate: Option<usize>,
+  tracer: Option<Box<dyn Write>>,
+  profiler: Option<Profiler>,
+  coverage: Option<Coverage>,
+  io: Io,
 }
 
 impl Machine {
-  pub fn new(global_vars: Vec<Option<Constant>>, global_var_names: Vec<String>) -> Machine {
+  /// 스냅샷으로 저장해 둔 상태에서 가상 머신을 되살립니다. `resume`을 호출하면 멈췄던 곳부터 이어서 실행합니다.
+  pub fn from_snapshot(snapshot: Snapshot) -> Machine {
+    Machine {
+      operand_stack: snapshot.operand_stack,
+      call_stack: snapshot.call_stack,
+      global_vars: snapshot.global_vars,
+      global_var_names: snapshot.global_var_names,
+      josa_table: snapshot.josa_table,
+      executed_count: snapshot.executed_count,
+      fuel: snapshot.fuel,
+      ..Default::default()
+    }
+  }
+
+  /// 지금 상태를 스냅샷으로 만듭니다. 명령어 사이에서만 멈추므로 언제 만들어도 이어서 실행할 수 있습니다.
+  pub fn snapshot(&self) -> Snapshot {
+    Snapshot {
+      operand_stack: self.operand_stack.clone(),
+      call_stack: self.call_stack.clone(),
+      global_vars: self.global_vars.clone(),
+      global_var_names: self.global_var_names.clone(),
+      josa_table: self.josa_table.clone(),
+      executed_count: self.executed_count,
+      fuel: self.fuel,
+    }
+  }
+
+  /// `josa_table`에는 프로그램과 내장 함수에 나오는 조사가 모두 등록되어 있어야 합니다.
+  pub fn new(
+    global_vars: Vec<Option<Constant>>,
+    global_var_names: Vec<String>,
+    josa_table: JosaTable,
+  ) -> Machine {
     let mut vars = global_vars;
     vars.resize(global_var_names.len(), None);
 
     Machine {
-      operand_stack: Vec::new(),
       global_vars: vars,
       global_var_names,
+      josa_table,
+      ..Default::default()
+    }
+  }
+
+  /// 실행할 수 있는 명령어의 개수를 정합니다. `None`이면 제한이 없습니다.
+  pub fn set_fuel(&mut self, fuel: Option<u64>) {
+    self.fuel = fuel;
+  }
+
+  /// 남은 명령어 실행 한도에 `amount`를 더합니다. 한도가 없는 경우에는 아무 일도 하지 않습니다.
+  pub fn add_fuel(&mut self, amount: u64) {
+    if let Some(fuel) = &mut self.fuel {
+      *fuel = fuel.saturating_add(amount);
+    }
+  }
+
+  pub fn fuel(&self) -> Option<u64> {
+    self.fuel
+  }
+
+  /// 이 시각이 지나면 실행을 멈춥니다. `None`이면 제한이 없습니다.
+  pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+    self.deadline = deadline;
+  }
+
+  pub fn set_memory_limits(&mut self, limits: MemoryLimits) {
+    self.memory_limits = limits;
+    self.heap_estimate = None;
+  }
+
+  /// 내장 함수가 출력하는 곳을 바꿉니다. 기본값은 표준 출력입니다.
+  pub fn set_output(&mut self, output: Box<dyn Output>) {
+    self.io.set_output(output);
+  }
+
+  pub fn output(&mut self) -> &mut dyn Output {
+    self.io.output()
+  }
+
+  /// 내장 함수가 입력을 읽는 곳을 바꿉니다. 기본값은 표준 입력입니다.
+  pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+    self.io.set_input(input);
+  }
+
+  /// 내장 함수에 넘겨주는 입출력을 `io`로 바꾸고, 쓰던 입출력을 돌려줍니다.
+  pub(crate) fn replace_io(&mut self, io: Io) -> Io {
+    mem::replace(&mut self.io, io)
+  }
+
+  /// 내장 함수가 접근할 수 있는 파일 경로를 정합니다. 기본값은 아무 곳에도 접근할 수 없습니다.
+  pub fn set_permissions(&mut self, permissions: Permissions) {
+    self.io.set_permissions(permissions);
+  }
+
+  /// 명령어를 실행할 때마다 실행 정보를 `tracer`에 기록합니다. `None`이면 기록하지 않습니다.
+  pub fn set_tracer(&mut self, tracer: Option<Box<dyn Write>>) {
+    self.tracer = tracer;
+  }
+
+  /// 실행 통계를 `profiler`에 모읍니다. 프로그램을 올리기 전에 설정해야 최상위 코드도 함께 측정됩니다.
+  pub fn set_profiler(&mut self, profiler: Option<Profiler>) {
+    self.profiler = profiler;
+  }
+
+  /// 모은 실행 통계를 돌려받습니다. 끝나지 않은 호출은 지금 끝난 것으로 칩니다.
+  pub fn take_profiler(&mut self) -> Option<Profiler> {
+    let mut profiler = self.profiler.take()?;
+    profiler.exit_all();
+    Some(profiler)
+  }
+
+  /// 실행된 줄을 `coverage`에 기록합니다. 프로그램을 올리기 전에 설정해야 합니다.
+  pub fn set_coverage(&mut self, coverage: Option<Coverage>) {
+    self.coverage = coverage;
+  }
+
+  /// 기록한 줄 커버리지를 돌려받습니다.
+  pub fn take_coverage(&mut self) -> Option<Coverage> {
+    let mut coverage = self.coverage.take()?;
+    coverage.exit_all();
+    Some(coverage)
+  }
+
+  /// 스택과 전역 변수에 있는 값들이 차지하는 메모리의 대략적인 크기(바이트)입니다. 여러 값이 공유하는 부분은 한 번만 셉니다.
+  pub fn heap_size(&self) -> usize {
+    let mut counter = SizeCounter::new();
+    let frame_vars = self
+      .call_stack
+      .iter()
+      .flat_map(|frame| frame.free_vars.iter());
+    self
+      .operand_stack
+      .iter()
+      .chain(self.global_vars.iter().flatten())
+      .chain(frame_vars)
+      .map(|value| value.approx_size(&mut counter))
+      .sum()
+  }
+
+  /// 지금까지 실행한 명령어의 개수입니다.
+  pub fn executed_instructions(&self) -> u64 {
+    self.executed_count
+  }
+
+  /// 실행 중인 프레임이 남아 있지 않으면 `true`입니다.
+  pub fn is_finished(&self) -> bool {
+    self.call_stack.is_empty()
+  }
+
+  /// `frame`을 최상위 프레임으로 하여 처음부터 실행합니다.
+  pub fn run(&mut self, frame: StackFrame) -> Result<(), (u32, HaneulError)> {
+    self.load(frame);
+    self.resume()
+  }
+
+  /// `frame`을 최상위 프레임으로 올려두기만 하고 실행하지는 않습니다.
+  pub fn load(&mut self, frame: StackFrame) {
+    self.operand_stack.clear();
+    self.call_stack.clear();
+    self.call_stack.push(frame);
+    if let Some(profiler) = &mut self.profiler {
+      profiler.exit_all();
+      profiler.enter(String::from(TOP_LEVEL_LABEL));
+    }
+    if let Some(coverage) = &mut self.coverage {
+      coverage.exit_all();
+      coverage.enter(TOP_LEVEL_LABEL);
+    }
+    self.unwind_finished_frames();
+  }
+
+  /// 멈춘 지점부터 실행을 계속합니다. 실행 한도나 제한 시간 때문에 멈춘 경우,
+  /// 한도를 늘린 뒤 이 함수를 다시 호출하면 이어서 실행됩니다.
+  pub fn resume(&mut self) -> Result<(), (u32, HaneulError)> {
+    while !self.is_finished() {
+      self.step()?;
+    }
+
+    Ok(())
+  }
+
+  /// 명령어 하나를 실행합니다. 실행을 마친 프레임은 곧바로 호출한 쪽으로 돌아가므로,
+  /// 이 함수가 끝나면 `current_line`은 다음에 실행할 명령어를 가리킵니다.
+  pub fn step(&mut self) -> Result<(), (u32, HaneulError)> {
+    let frame = match self.call_stack.last() {
+      Some(frame) => frame,
+      None => return Ok(()),
+    };
+
+    let current_inst = &frame.code[frame.ip];
+    let line_number = current_inst.line_number;
+    let opcode_name = current_inst.opcode.name();
+    // 값을 불러오는 명령어는 이미 있는 값을 공유할 뿐이므로, 새 값을 만드는 명령어만 힙을 키웁니다.
+    let may_grow = matches!(
+      current_inst.opcode,
+      Opcode::Call(_)
+        | Opcode::BinaryOp(_)
+        | Opcode::UnaryOp(_)
+        | Opcode::FreeVarLocal(_)
+        | Opcode::FreeVarFree(_)
+    );
+    self.check_limits().map_err(|err| (line_number, err))?;
+
+    if self.tracer.is_some() {
+      self.trace();
+    }
+
+    if let Some(profiler) = &mut self.profiler {
+      profiler.record_instruction(opcode_name, line_number);
+    }
+
+    if let Some(coverage) = &mut self.coverage {
+      coverage.record_line(line_number);
     }
+
+    self.executed_count += 1;
+    self.execute().map_err(|err| (line_number, err))?;
+    self
+      .check_memory(may_grow)
+      .map_err(|err| (line_number, err))?;
+
+    self.unwind_finished_frames();
+    Ok(())
   }
 
-  pub fn run(&mut self, frame: &StackFrame) -> Result<(), (u32, HaneulError)> {
-    let mut ip = 0;
-    let code_length = frame.code.len();
+  /// 다음에 실행할 명령어의 라인 번호입니다.
+  pub fn current_line(&self) -> Option<u32> {
+    let frame = self.call_stack.last()?;
+    Some(frame.code[frame.ip].line_number)
+  }
+
+  pub fn call_stack(&self) -> &[StackFrame] {
+    &self.call_stack
+  }
 
-    let result = 'outer: loop {
-      if ip >= code_length {
-        break Ok(());
+  pub fn operand_stack(&self) -> &[Constant] {
+    &self.operand_stack
+  }
+
+  /// `frame`의 인수 자리마다의 이름입니다. 나머지 인수 목록은 "나머지"라고 부릅니다.
+  pub fn local_names(&self, frame: &StackFrame) -> Vec<&str> {
+    let mut names: Vec<&str> = frame
+      .josa_list
+      .iter()
+      .map(|josa| self.josa_table.name(*josa))
+      .collect();
+    if frame.variadic {
+      names.push("나머지");
+    }
+    names
+  }
+
+  /// `frame`의 인수들을 조사 이름과 함께 돌려줍니다.
+  pub fn locals<'a>(&'a self, frame: &'a StackFrame) -> Vec<(&'a str, &'a Constant)> {
+    self
+      .local_names(frame)
+      .into_iter()
+      .zip(&self.operand_stack[frame.slot_start..])
+      .collect()
+  }
+
+  pub fn josa_table(&self) -> &JosaTable {
+    &self.josa_table
+  }
+
+  /// 이름이 `name`인 전역 변수를 찾습니다. 그런 변수가 없으면 `None`,
+  /// 아직 값이 정해지지 않았으면 `Some(None)`입니다.
+  pub fn global(&self, name: &str) -> Option<Option<&Constant>> {
+    let index = self.global_var_names.iter().position(|x| x == name)?;
+    Some(self.global_vars[index].as_ref())
+  }
+
+  pub fn global_var_names(&self) -> &[String] {
+    &self.global_var_names
+  }
+
+  pub(crate) fn global_vars(&self) -> &[Option<Constant>] {
+    &self.global_vars
+  }
+
+  fn check_limits(&mut self) -> Result<(), HaneulError> {
+    if let Some(deadline) = self.deadline {
+      if self.executed_count.is_multiple_of(DEADLINE_CHECK_INTERVAL) && Instant::now() >= deadline {
+        return Err(HaneulError::DeadlineExceeded {
+          executed: self.executed_count,
+        });
       }
+    }
 
-      let current_inst = &frame.code[ip];
-      // println!("{:?}", self.operand_stack);
-      // println!("-----------------");
+    if let Some(fuel) = &mut self.fuel {
+      if *fuel == 0 {
+        return Err(HaneulError::OutOfFuel {
+          executed: self.executed_count,
+        });
+      }
+      *fuel -= 1;
+    }
 
-      match &current_inst.opcode {
-        Opcode::Push(v) => {
-          self
-            .operand_stack
-            .push(frame.const_table[*v as usize].clone());
+    Ok(())
+  }
+
+  fn trace(&mut self) {
+    let frame = self.call_stack.last().unwrap();
+    let current_inst = &frame.code[frame.ip];
+    let top = match self.operand_stack.last() {
+      Some(value) => format!("{:?}", value),
+      None => String::from("(빈 스택)"),
+    };
+
+    let opcode = match &current_inst.opcode {
+      Opcode::Call(josa_list) => {
+        let names: Vec<&str> = josa_list
+          .iter()
+          .map(|josa| self.josa_table.name(*josa))
+          .collect();
+        format!("Call({})", names.join(", "))
+      }
+      opcode => format!("{:?}", opcode),
+    };
+
+    if let Some(tracer) = &mut self.tracer {
+      let _ = writeln!(
+        tracer,
+        "{:>3} {:>5} {:>5}  {:<32} {}",
+        self.call_stack.len(),
+        frame.ip,
+        current_inst.line_number,
+        opcode,
+        top
+      );
+    }
+  }
+
+  fn check_memory(&mut self, may_grow: bool) -> Result<(), HaneulError> {
+    if let Some(limit) = self.memory_limits.max_stack_size {
+      if self.operand_stack.len() > limit {
+        return Err(HaneulError::StackOverflow { limit });
+      }
+    }
+
+    if let Some(limit) = self.memory_limits.max_heap_size {
+      let created = match self.operand_stack.last() {
+        Some(value @ Constant::Str(_))
+        | Some(value @ Constant::List(_))
+        | Some(value @ Constant::Function { .. })
+          if may_grow =>
+        {
+          value
+        }
+        _ => return Ok(()),
+      };
+
+      // 방금 만든 값 가운데 다른 곳과 공유하지 않는 부분만 새로 늘어난 크기로 어림합니다.
+      let estimate = match self.heap_estimate {
+        Some(estimate) => estimate + created.approx_size(&mut SizeCounter::unique()),
+        None => self.heap_size(),
+      };
+      let size = if estimate > limit {
+        self.heap_size()
+      } else {
+        estimate
+      };
+      self.heap_estimate = Some(size);
+      if size > limit {
+        return Err(HaneulError::HeapLimitExceeded { limit, size });
+      }
+    }
+
+    Ok(())
+  }
+
+  fn unwind_finished_frames(&mut self) {
+    while let Some(frame) = self.call_stack.last() {
+      if frame.ip < frame.code.len() {
+        break;
+      }
+
+      let frame = self.call_stack.pop().unwrap();
+      if let Some(profiler) = &mut self.profiler {
+        profiler.exit();
+      }
+      if let Some(coverage) = &mut self.coverage {
+        coverage.exit();
+      }
+
+      if self.call_stack.is_empty() {
+        break;
+      }
+
+      let result = self.operand_stack.pop().unwrap();
+      self.operand_stack.truncate(frame.slot_start);
+      self.operand_stack.push(result);
+    }
+  }
+
+  fn execute(&mut self) -> Result<(), HaneulError> {
+    let frame = self.call_stack.last_mut().unwrap();
+    let current_inst = &frame.code[frame.ip];
+    frame.ip += 1;
+
+    match &current_inst.opcode {
+      Opcode::Push(v) => {
+        self
+          .operand_stack
+          .push(frame.const_table[*v as usize].clone());
+      }
+      Opcode::Pop => {
+        self.operand_stack.pop();
+      }
+      Opcode::Load(v) => {
+        self
+          .operand_stack
+          .push(self.operand_stack[frame.slot_start + *v as usize].clone());
+      }
+      Opcode::LoadDeref(v) => {
+        self
+          .operand_stack
+          .push(frame.free_vars[*v as usize].clone());
+      }
+      Opcode::LoadGlobal(v) => {
+        if let Some(value) = &self.global_vars[*v as usize] {
+          self.operand_stack.push(value.clone());
+        } else {
+          return Err(HaneulError::UnboundVariable {
+            var_name: self.global_var_names[*v as usize].clone(),
+          });
         }
-        Opcode::Pop => {
-          self.operand_stack.pop();
+      }
+      Opcode::StoreGlobal(v) => {
+        self.global_vars[*v as usize] = Some(self.operand_stack.pop().unwrap());
+      }
+      Opcode::Call(_) => {
+        // 조사 목록을 복사하지 않도록 코드를 공유한 채로 호출합니다.
+        let code = Rc::clone(&frame.code);
+        let caches = Rc::clone(&frame.caches);
+        let ip = frame.ip - 1;
+        if let Opcode::Call(given_josa_list) = &code[ip].opcode {
+          return self.call(given_josa_list, &caches, ip);
         }
-        Opcode::Load(v) => {
-          self
-            .operand_stack
-            .push(self.operand_stack[frame.slot_start + *v as usize].clone());
+      }
+      Opcode::Jmp(v) => {
+        frame.ip = *v as usize;
+      }
+      Opcode::PopJmpIfFalse(v) => {
+        let top = self.operand_stack.pop().unwrap();
+        match top {
+          Constant::Boolean(value) => {
+            if !value {
+              frame.ip = *v as usize;
+            }
+          }
+          _ => return Err(HaneulError::ExpectedBoolean { value: top }),
+        };
+      }
+      Opcode::FreeVarLocal(index) => {
+        let value = self.operand_stack[frame.slot_start + *index as usize].clone();
+
+        let top = self.operand_stack.last_mut().unwrap();
+        if let Constant::Function {
+          func_object: FuncObject::CodeObject { free_vars, .. },
+          ..
+        } = top
+        {
+          Rc::make_mut(free_vars).push(value);
+        } else {
+          panic!("FreeVarLocal은 스택의 최상위가 코드 객체인 경우에만 사용 가능합니다.");
         }
-        Opcode::LoadDeref(v) => {
-          self
-            .operand_stack
-            .push(frame.free_vars[*v as usize].clone());
+      }
+      Opcode::FreeVarFree(index) => {
+        let value = frame.free_vars[*index as usize].clone();
+
+        let top = self.operand_stack.last_mut().unwrap();
+        if let Constant::Function {
+          func_object: FuncObject::CodeObject { free_vars, .. },
+          ..
+        } = top
+        {
+          Rc::make_mut(free_vars).push(value);
+        } else {
+          panic!("FreeVarFree는 스택의 최상위가 코드 객체인 경우에만 사용 가능합니다.");
         }
-        Opcode::LoadGlobal(v) => {
-          if let Some(value) = &self.global_vars[*v as usize] {
-            self.operand_stack.push(value.clone());
-          } else {
-            break Err(HaneulError::UnboundVariable {
-              var_name: self.global_var_names[*v as usize].clone(),
-            });
+      }
+      Opcode::UnaryOp(op) => {
+        let value = self.operand_stack.pop().unwrap();
+        let result = match op {
+          UnaryOp::Negate => -&value,
+        };
+
+        match result {
+          Some(result_value) => self.operand_stack.push(result_value),
+          None => {
+            return Err(HaneulError::InvalidUnaryOp {
+              value,
+              op: op.clone(),
+            })
           }
         }
-        Opcode::StoreGlobal(v) => {
-          self.global_vars[*v as usize] = Some(self.operand_stack.pop().unwrap());
+      }
+      Opcode::BinaryOp(op) => {
+        let rhs = self.operand_stack.pop().unwrap();
+        let lhs = self.operand_stack.pop().unwrap();
+
+        let result = match op {
+          BinaryOp::Add => &lhs + &rhs,
+          BinaryOp::Subtract => &lhs - &rhs,
+          BinaryOp::Multiply => &lhs * &rhs,
+          BinaryOp::Divide => &lhs / &rhs,
+          BinaryOp::Mod => &lhs % &rhs,
+          BinaryOp::Cmp(ord) => {
+            PartialOrd::partial_cmp(&lhs, &rhs).map(|v| Constant::Boolean(v == *ord))
+          }
+        };
+
+        match result {
+          Some(result_value) => self.operand_stack.push(result_value),
+          None => {
+            return Err(HaneulError::InvalidBinaryOp {
+              lhs: Box::new(lhs),
+              rhs: Box::new(rhs),
+              op: op.clone(),
+            })
+          }
         }
-        Opcode::Call(given_josa_list) => {
-          let given_arity = given_josa_list.len() as u8;
-          let value = self.operand_stack.pop().unwrap();
-
-          if let Constant::Function {
-            mut josa_map,
-            func_object,
-          } = value
-          {
-            let full_arity = josa_map.len();
-            let actual_arity = josa_map.values().filter(|x| **x == None).count() as u8;
-
-            if given_arity > actual_arity {
-              break Err(HaneulError::TooManyArgs {
-                actual_arity,
-                given_arity,
-              });
-            }
+      }
+    }
 
-            for josa in given_josa_list {
-              if josa == "_" {
-                for value in josa_map.values_mut() {
-                  if *value == None {
-                    *value = self.operand_stack.pop();
-                    break;
-                  }
-                }
-
-                continue;
-              }
-
-              match josa_map.get_mut(josa) {
-                Some(value) => match value {
-                  Some(_) => {
-                    break 'outer Err(HaneulError::AlreadyAppliedJosa { josa: josa.clone() })
-                  }
-                  None => {
-                    *value = self.operand_stack.pop();
-                  }
-                },
-                None => break 'outer Err(HaneulError::UnboundJosa { josa: josa.clone() }),
-              }
-            }
+    Ok(())
+  }
 
-            if given_arity < actual_arity {
-              self.operand_stack.push(Constant::Function {
-                josa_map,
-                func_object,
-              });
-              continue;
-            }
+  /// `ip`는 호출 명령어의 위치로, 그 명령어의 인라인 캐시를 찾는 데 씁니다.
+  fn call(
+    &mut self,
+    given_josa_list: &[JosaId],
+    caches: &InlineCaches,
+    ip: usize,
+  ) -> Result<(), HaneulError> {
+    let given_arity = given_josa_list.len() as u8;
+    let value = self.operand_stack.pop().unwrap();
+
+    let (josa_map, mut rest_args, func_object) = match value {
+      Constant::Function {
+        josa_map,
+        rest_args,
+        func_object,
+      } => (josa_map, rest_args, func_object),
+      value => return Err(HaneulError::NotCallable { value }),
+    };
+    let mut args: Vec<Option<Constant>> = josa_map.values().cloned().collect();
+
+    // 지난번과 같은 모양의 함수를 호출한다면 인수가 들어갈 자리를 다시 찾지 않습니다.
+    if let Some(cache) = caches.call(ip) {
+      if cache.matches(&josa_map, rest_args.is_some()) {
+        for position in &cache.positions {
+          let value = self.operand_stack.pop();
+          match position {
+            ArgPosition::Param(index) => args[*index] = value,
+            ArgPosition::Rest => Rc::make_mut(rest_args.as_mut().unwrap()).push(value.unwrap()),
+          }
+        }
+
+        let josa_list = josa_map.keys().collect();
+        let args = args.into_iter().map(Option::unwrap).collect();
+        return self.invoke(func_object, josa_list, args, rest_args);
+      }
+    }
+
+    let actual_arity = args.iter().filter(|x| x.is_none()).count() as u8;
+
+    if rest_args.is_none() && given_arity > actual_arity {
+      return Err(HaneulError::TooManyArgs {
+        actual_arity,
+        given_arity,
+        signature: josa_signature(&josa_map, &rest_args, &self.josa_table),
+      });
+    }
 
-            let mut args: Vec<Constant> = josa_map.into_iter().map(|(_, x)| x.unwrap()).collect();
-
-            match func_object {
-              FuncObject::CodeObject {
-                code,
-                const_table,
-                free_vars,
-              } => {
-                let func_frame = StackFrame {
-                  code,
-                  const_table,
-                  free_vars,
-                  slot_start: self.operand_stack.len(),
-                };
-
-                self.operand_stack.append(&mut args);
-                self.run(&func_frame)?;
-
-                let result = self.operand_stack.pop().unwrap();
-
-                for _ in 0..full_arity {
-                  self.operand_stack.pop();
-                }
-
-                self.operand_stack.push(result)
-              }
-              FuncObject::NativeFunc { function } => {
-                self.operand_stack.push(function(args));
-              }
+    let mut positions = Vec::with_capacity(given_josa_list.len());
+    for josa in given_josa_list {
+      if *josa == BLANK {
+        let empty_slot = args.iter().position(|x| x.is_none());
+        match empty_slot {
+          Some(index) => {
+            args[index] = self.operand_stack.pop();
+            positions.push(ArgPosition::Param(index));
+          }
+          None => {
+            if let Some(rest) = &mut rest_args {
+              Rc::make_mut(rest).push(self.operand_stack.pop().unwrap());
+              positions.push(ArgPosition::Rest);
             }
-          } else {
-            break Err(HaneulError::NotCallable { value });
           }
         }
-        Opcode::Jmp(v) => {
-          ip = *v as usize;
-          continue;
+
+        continue;
+      }
+
+      match josa_map.position(*josa) {
+        Some(index) if args[index].is_none() => {
+          args[index] = self.operand_stack.pop();
+          positions.push(ArgPosition::Param(index));
         }
-        Opcode::PopJmpIfFalse(v) => {
-          let top = self.operand_stack.pop().unwrap();
-          match top {
-            Constant::Boolean(value) => {
-              if !value {
-                ip = *v as usize;
-                continue;
-              }
-            }
-            _ => break Err(HaneulError::ExpectedBoolean { value: top }),
-          };
+        Some(_) => {
+          let name = self.josa_table.name(*josa);
+          let applied = applied_map(&josa_map, args);
+          let signature = josa_signature(&applied, &rest_args, &self.josa_table);
+          return Err(HaneulError::AlreadyAppliedJosa {
+            josa: name.to_string(),
+            suggestion: signature.suggest(name),
+            signature,
+          });
         }
-        Opcode::FreeVarLocal(index) => {
-          let value = self.operand_stack[frame.slot_start + *index as usize].clone();
-
-          let top = self.operand_stack.last_mut().unwrap();
-          if let Constant::Function {
-            func_object: FuncObject::CodeObject { free_vars, .. },
-            ..
-          } = top
-          {
-            free_vars.push(value);
-          } else {
-            panic!("FreeVarLocal은 스택의 최상위가 코드 객체인 경우에만 사용 가능합니다.");
-          }
+        None => {
+          let name = self.josa_table.name(*josa);
+          let applied = applied_map(&josa_map, args);
+          let signature = josa_signature(&applied, &rest_args, &self.josa_table);
+          return Err(HaneulError::UnboundJosa {
+            josa: name.to_string(),
+            suggestion: signature.suggest(name),
+            signature,
+          });
         }
-        Opcode::FreeVarFree(index) => {
-          let value = frame.free_vars[*index as usize].clone();
-
-          let top = self.operand_stack.last_mut().unwrap();
-          if let Constant::Function {
-            func_object: FuncObject::CodeObject { free_vars, .. },
-            ..
-          } = top
-          {
-            free_vars.push(value);
-          } else {
-            panic!("FreeVarFree는 스택의 최상위가 코드 객체인 경우에만 사용 가능합니다.");
+      }
+    }
+
+    // 아직 인수가 모자라면 넘긴 인수까지 들어간 새 조사 목록으로 함수를 만듭니다.
+    if args.iter().any(|x| x.is_none()) {
+      self.operand_stack.push(Constant::Function {
+        josa_map: Rc::new(applied_map(&josa_map, args)),
+        rest_args,
+        func_object,
+      });
+      return Ok(());
+    }
+
+    caches.set_call(
+      ip,
+      CallCache {
+        slots: josa_map
+          .iter()
+          .map(|(josa, x)| (*josa, x.is_some()))
+          .collect(),
+        variadic: rest_args.is_some(),
+        positions,
+      },
+    );
+
+    let josa_list = josa_map.keys().collect();
+    let args = args.into_iter().map(Option::unwrap).collect();
+    self.invoke(func_object, josa_list, args, rest_args)
+  }
+
+  /// 모든 인수가 채워진 함수를 실행합니다.
+  fn invoke(
+    &mut self,
+    func_object: FuncObject,
+    josa_list: Vec<JosaId>,
+    mut args: Vec<Constant>,
+    rest_args: Option<Rc<Vec<Constant>>>,
+  ) -> Result<(), HaneulError> {
+    let variadic = rest_args.is_some();
+    if let Some(rest) = rest_args {
+      args.push(Constant::List(rest));
+    }
+
+    match func_object {
+      FuncObject::CodeObject {
+        code,
+        const_table,
+        free_vars,
+        caches,
+      } => {
+        if let Some(limit) = self.memory_limits.max_call_depth {
+          if self.call_stack.len() >= limit {
+            return Err(HaneulError::CallDepthExceeded { limit });
           }
         }
-        Opcode::UnaryOp(op) => {
-          let value = self.operand_stack.pop().unwrap();
-          let result = match op {
-            UnaryOp::Negate => -&value,
-          };
-
-          match result {
-            Some(result_value) => self.operand_stack.push(result_value),
-            None => {
-              break Err(HaneulError::InvalidUnaryOp {
-                value,
-                op: op.clone(),
-              })
-            }
+
+        self.call_stack.push(StackFrame {
+          code,
+          const_table,
+          free_vars,
+          caches,
+          slot_start: self.operand_stack.len(),
+          ip: 0,
+          josa_list,
+          variadic,
+        });
+
+        if self.profiler.is_some() || self.coverage.is_some() {
+          let frame = self.call_stack.last().unwrap();
+          let label = function_label(&self.local_names(frame), &frame.code);
+          if let Some(coverage) = &mut self.coverage {
+            coverage.enter(&label);
           }
-        }
-        Opcode::BinaryOp(op) => {
-          let rhs = self.operand_stack.pop().unwrap();
-          let lhs = self.operand_stack.pop().unwrap();
-
-          let result = match op {
-            BinaryOp::Add => &lhs + &rhs,
-            BinaryOp::Subtract => &lhs - &rhs,
-            BinaryOp::Multiply => &lhs * &rhs,
-            BinaryOp::Divide => &lhs / &rhs,
-            BinaryOp::Mod => &lhs % &rhs,
-            BinaryOp::Cmp(ord) => {
-              PartialOrd::partial_cmp(&lhs, &rhs).map(|v| Constant::Boolean(v == *ord))
-            }
-          };
-
-          match result {
-            Some(result_value) => self.operand_stack.push(result_value),
-            None => {
-              break Err(HaneulError::InvalidBinaryOp {
-                lhs,
-                rhs,
-                op: op.clone(),
-              })
-            }
+          if let Some(profiler) = &mut self.profiler {
+            profiler.enter(label);
           }
         }
+
+        self.operand_stack.append(&mut args);
+      }
+      FuncObject::NativeFunc { name, function } => {
+        if let Some(profiler) = &mut self.profiler {
+          profiler.enter(String::from(name));
+        }
+
+        let result = function(&mut self.io, args);
+
+        if let Some(profiler) = &mut self.profiler {
+          profiler.exit();
+        }
+        self.operand_stack.push(result?);
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn inst(opcode: Opcode) -> Instruction {
+    Instruction {
+      line_number: 1,
+      opcode,
+    }
+  }
+
+  #[test]
+  fn resume_after_refueling() {
+    let mut machine = Machine::new(Vec::new(), vec![String::from("합")], JosaTable::new());
+    machine.set_fuel(Some(5));
+
+    // 합 = 0; 합 = 합 + 1 을 두 번
+    let code = vec![
+      inst(Opcode::Push(0)),
+      inst(Opcode::StoreGlobal(0)),
+      inst(Opcode::LoadGlobal(0)),
+      inst(Opcode::Push(1)),
+      inst(Opcode::BinaryOp(BinaryOp::Add)),
+      inst(Opcode::StoreGlobal(0)),
+      inst(Opcode::LoadGlobal(0)),
+      inst(Opcode::Push(1)),
+      inst(Opcode::BinaryOp(BinaryOp::Add)),
+      inst(Opcode::StoreGlobal(0)),
+    ];
+
+    let result = machine.run(StackFrame::new(
+      code,
+      vec![Constant::Integer(0), Constant::Integer(1)],
+    ));
+    assert!(matches!(
+      result,
+      Err((1, HaneulError::OutOfFuel { executed: 5 }))
+    ));
+    assert!(!machine.is_finished());
+
+    machine.add_fuel(5);
+    assert!(machine.resume().is_ok());
+    assert!(machine.is_finished());
+    assert_eq!(machine.executed_instructions(), 10);
+    assert_eq!(machine.global_vars[0], Some(Constant::Integer(2)));
+  }
+
+  #[test]
+  fn stop_infinite_loop_at_deadline() {
+    let mut machine = Machine::new(Vec::new(), Vec::new(), JosaTable::new());
+    machine.set_deadline(Some(Instant::now()));
+
+    let result = machine.run(StackFrame::new(vec![inst(Opcode::Jmp(0))], Vec::new()));
+    assert!(matches!(
+      result,
+      Err((1, HaneulError::DeadlineExceeded { .. }))
+    ));
+  }
+
+  #[test]
+  fn limit_stack_size() {
+    let mut machine = Machine::new(Vec::new(), Vec::new(), JosaTable::new());
+    machine.set_memory_limits(MemoryLimits {
+      max_stack_size: Some(100),
+      ..Default::default()
+    });
+
+    let code = vec![inst(Opcode::Push(0)), inst(Opcode::Jmp(0))];
+    let result = machine.run(StackFrame::new(code, vec![Constant::Integer(0)]));
+    assert!(matches!(
+      result,
+      Err((1, HaneulError::StackOverflow { limit: 100 }))
+    ));
+  }
+
+  #[test]
+  fn limit_call_depth() {
+    let mut machine = Machine::new(Vec::new(), vec![String::from("재귀")], JosaTable::new());
+    machine.set_memory_limits(MemoryLimits {
+      max_call_depth: Some(50),
+      ..Default::default()
+    });
+
+    let recurse = Constant::Function {
+      josa_map: Rc::default(),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![
+          inst(Opcode::LoadGlobal(0)),
+          inst(Opcode::Call(Vec::new())),
+        ]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+    let code = vec![
+      inst(Opcode::Push(0)),
+      inst(Opcode::StoreGlobal(0)),
+      inst(Opcode::LoadGlobal(0)),
+      inst(Opcode::Call(Vec::new())),
+    ];
+
+    let result = machine.run(StackFrame::new(code, vec![recurse]));
+    assert!(matches!(
+      result,
+      Err((1, HaneulError::CallDepthExceeded { limit: 50 }))
+    ));
+    assert_eq!(machine.call_stack.len(), 50);
+  }
+
+  #[test]
+  fn limit_heap_size() {
+    let mut machine = Machine::new(Vec::new(), Vec::new(), JosaTable::new());
+    machine.set_memory_limits(MemoryLimits {
+      max_heap_size: Some(4096),
+      ..Default::default()
+    });
+
+    let closure = Constant::Function {
+      josa_map: Rc::default(),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::default(),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+
+    // 스택의 첫 번째 값을 계속 자유 변수로 붙여 클로저를 키웁니다.
+    let code = vec![
+      inst(Opcode::Push(0)),
+      inst(Opcode::FreeVarLocal(0)),
+      inst(Opcode::Jmp(1)),
+    ];
+    let result = machine.run(StackFrame::new(code, vec![closure]));
+    assert!(matches!(
+      result,
+      Err((1, HaneulError::HeapLimitExceeded { limit: 4096, .. }))
+    ));
+  }
+
+  #[test]
+  fn count_shared_function_once() {
+    let mut machine = Machine::new(Vec::new(), Vec::new(), JosaTable::new());
+    machine.set_memory_limits(MemoryLimits {
+      max_heap_size: Some(32768),
+      ..Default::default()
+    });
+
+    let function = Constant::Function {
+      josa_map: Rc::default(),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![inst(Opcode::Pop); 500]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+    let function_size = function.approx_size(&mut SizeCounter::new());
+    assert!(function_size > 32768 / 3);
+
+    // 같은 코드를 공유하는 클로저를 50개 만들어도 코드는 한 번만 셉니다.
+    let mut code = vec![inst(Opcode::Push(1))];
+    for _ in 0..50 {
+      code.extend(vec![inst(Opcode::Push(0)), inst(Opcode::FreeVarLocal(0))]);
+    }
+    let const_table = vec![function, Constant::Integer(1)];
+    assert!(machine.run(StackFrame::new(code, const_table)).is_ok());
+    assert_eq!(machine.operand_stack.len(), 51);
+    assert!(machine.heap_size() < function_size * 2);
+  }
+
+  #[test]
+  fn trace_each_instruction() {
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+      fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
       }
 
-      ip += 1;
+      fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+      }
+    }
+
+    let buffer = Rc::new(RefCell::new(Vec::new()));
+    let mut machine = Machine::new(Vec::new(), Vec::new(), JosaTable::new());
+    machine.set_tracer(Some(Box::new(SharedBuffer(buffer.clone()))));
+
+    let code = vec![inst(Opcode::Push(0)), inst(Opcode::Pop)];
+    assert!(machine
+      .run(StackFrame::new(code, vec![Constant::Integer(7)]))
+      .is_ok());
+
+    let output = String::from_utf8(buffer.borrow().clone()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("Push(0)") && lines[0].ends_with("(빈 스택)"));
+    assert!(lines[1].contains("Pop") && lines[1].ends_with("Integer(7)"));
+  }
+
+  #[test]
+  fn share_function_and_copy_on_partial_application() {
+    let mut josa_table = JosaTable::new();
+    let eul = josa_table.intern("을");
+    let e = josa_table.intern("에");
+
+    let add = Constant::Function {
+      josa_map: Rc::new(JosaMap::new(vec![eul, e])),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![
+          inst(Opcode::Load(0)),
+          inst(Opcode::Load(1)),
+          inst(Opcode::BinaryOp(BinaryOp::Add)),
+        ]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
     };
 
+    // 하나_더하기 = 더하기(에 1)
+    let code = vec![
+      inst(Opcode::Push(0)),
+      inst(Opcode::LoadGlobal(0)),
+      inst(Opcode::Call(vec![e])),
+      inst(Opcode::StoreGlobal(1)),
+    ];
+
+    let mut machine = Machine::new(
+      vec![Some(add)],
+      vec![String::from("더하기"), String::from("하나_더하기")],
+      josa_table,
+    );
+    assert!(machine
+      .run(StackFrame::new(code, vec![Constant::Integer(1)]))
+      .is_ok());
+
+    let (original, applied) = match (
+      machine.global("더하기").unwrap().unwrap(),
+      machine.global("하나_더하기").unwrap().unwrap(),
+    ) {
+      (
+        Constant::Function {
+          josa_map: original,
+          func_object: FuncObject::CodeObject { code: code1, .. },
+          ..
+        },
+        Constant::Function {
+          josa_map: applied,
+          func_object: FuncObject::CodeObject { code: code2, .. },
+          ..
+        },
+      ) => {
+        assert!(Rc::ptr_eq(code1, code2));
+        (original, applied)
+      }
+      _ => unreachable!(),
+    };
+
+    assert_eq!(original.get(e), Some(&None));
+    assert_eq!(applied.get(e), Some(&Some(Constant::Integer(1))));
+  }
+
+  #[test]
+  fn report_josa_names_in_errors() {
+    let mut josa_table = JosaTable::new();
+    let eul = josa_table.intern("을");
+    let reul = josa_table.intern("를");
+
+    let identity = Constant::Function {
+      josa_map: Rc::new(JosaMap::new(vec![eul])),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![inst(Opcode::Load(0))]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+
+    let code = vec![
+      inst(Opcode::Push(1)),
+      inst(Opcode::Push(0)),
+      inst(Opcode::Call(vec![reul])),
+    ];
+
+    let mut machine = Machine::new(Vec::new(), Vec::new(), josa_table);
+    let result = machine.run(StackFrame::new(code, vec![identity, Constant::Integer(1)]));
     match result {
-      Ok(_) => Ok(()),
-      Err(err) => Err((frame.code[ip].line_number, err)),
+      Err((
+        1,
+        HaneulError::UnboundJosa {
+          josa, suggestion, ..
+        },
+      )) => {
+        assert_eq!(josa, "를");
+        assert_eq!(suggestion, Some(String::from("을")));
+      }
+      _ => panic!("조사 에러가 나야 합니다."),
+    }
+  }
+
+  #[test]
+  fn reuse_call_cache_only_for_same_callee() {
+    let mut josa_table = JosaTable::new();
+    let reul = josa_table.intern("를");
+    let eul = josa_table.intern("을");
+    let e = josa_table.intern("에");
+
+    let function = |josa_list: Vec<JosaId>, code: Vec<Instruction>| Constant::Function {
+      josa_map: Rc::new(JosaMap::new(josa_list)),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(code),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+
+    // 함수를 을 에 호출하기
+    let apply = function(
+      vec![reul, eul, e],
+      vec![
+        inst(Opcode::Load(2)),
+        inst(Opcode::Load(1)),
+        inst(Opcode::Load(0)),
+        inst(Opcode::Call(vec![eul, e])),
+      ],
+    );
+    let subtract = |josa_list| {
+      function(
+        josa_list,
+        vec![
+          inst(Opcode::Load(0)),
+          inst(Opcode::Load(1)),
+          inst(Opcode::BinaryOp(BinaryOp::Subtract)),
+        ],
+      )
+    };
+    // 을에서 에를 빼는 함수와, 자리 순서가 반대라서 에에서 을을 빼는 함수
+    let eul_minus_e = subtract(vec![eul, e]);
+    let e_minus_eul = subtract(vec![e, eul]);
+
+    let mut code = Vec::new();
+    for (index, callee) in [1, 1, 2].iter().enumerate() {
+      code.extend(vec![
+        inst(Opcode::Push(4)),
+        inst(Opcode::Push(3)),
+        inst(Opcode::Push(*callee)),
+        inst(Opcode::Push(0)),
+        inst(Opcode::Call(vec![reul, eul, e])),
+        inst(Opcode::StoreGlobal(index as u32)),
+      ]);
     }
+
+    let names = vec![String::new(), String::new(), String::new()];
+    let mut machine = Machine::new(Vec::new(), names, josa_table);
+    let const_table = vec![
+      apply.clone(),
+      eul_minus_e,
+      e_minus_eul.clone(),
+      Constant::Integer(10),
+      Constant::Integer(3),
+    ];
+    assert!(machine.run(StackFrame::new(code, const_table)).is_ok());
+    assert_eq!(
+      machine.global_vars,
+      vec![
+        Some(Constant::Integer(7)),
+        Some(Constant::Integer(7)),
+        Some(Constant::Integer(-7))
+      ]
+    );
+
+    match (apply, e_minus_eul) {
+      (
+        Constant::Function {
+          func_object: FuncObject::CodeObject { caches, .. },
+          ..
+        },
+        Constant::Function { josa_map, .. },
+      ) => {
+        let cache = caches.call(3).unwrap();
+        assert!(cache.matches(&josa_map, false));
+        assert_eq!(
+          cache.positions,
+          vec![ArgPosition::Param(1), ArgPosition::Param(0)]
+        );
+      }
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn print_strings_as_text() {
+    use crate::builtin::get_builtin;
+    use crate::io::MemoryOutput;
+    use std::io::Cursor;
+
+    let mut josa_table = JosaTable::new();
+    let eul = josa_table.intern("을");
+    let names = vec![String::from("출력"), String::from("입력")];
+    let globals = get_builtin(&mut josa_table, &names);
+    let mut machine = Machine::new(globals, names, josa_table);
+    let output = MemoryOutput::new();
+    machine.set_output(Box::new(output.clone()));
+    machine.set_input(Box::new(Cursor::new("하늘\n")));
+
+    // 출력("안녕", 1); 출력(입력())
+    let code = vec![
+      inst(Opcode::Push(1)),
+      inst(Opcode::Push(0)),
+      inst(Opcode::LoadGlobal(0)),
+      inst(Opcode::Call(vec![eul, BLANK])),
+      inst(Opcode::Pop),
+      inst(Opcode::LoadGlobal(1)),
+      inst(Opcode::Call(Vec::new())),
+      inst(Opcode::LoadGlobal(0)),
+      inst(Opcode::Call(vec![eul])),
+    ];
+    let const_table = vec![Constant::Str("안녕".into()), Constant::Integer(1)];
+    assert!(machine.run(StackFrame::new(code, const_table)).is_ok());
+    assert_eq!(output.contents(), "안녕 1\n하늘\n");
   }
 }
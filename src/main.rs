@@ -1,59 +1,263 @@
-mod builtin;
-mod constant;
-mod error;
-mod funcobject;
-mod instruction;
-mod machine;
-mod opcode;
-mod parser;
-mod program;
-
 use std::env;
-use std::fs;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
 
-use builtin::get_builtin;
-use machine::{Machine, StackFrame};
-use parser::program;
-use program::Program;
+use haneul::coverage::Coverage;
+use haneul::dap::DapServer;
+use haneul::debugger::Debugger;
+use haneul::encoder::encode;
+use haneul::linker::{link, LinkUnit};
+use haneul::machine::{Machine, StackFrame};
+use haneul::module::ModuleLoader;
+use haneul::optimizer::optimize;
+use haneul::parser::program;
+use haneul::permission::Permissions;
+use haneul::profiler::Profiler;
+use haneul::program::Program;
 
-fn main() {
-  let mut args = env::args();
-  if args.len() != 2 {
-    println!("파일 이름을 입력해주세요.");
-    std::process::exit(1);
-  }
-
-  let filename = &args.nth(1).unwrap();
-  if let Ok(data) = fs::read(filename) {
-    let result = program(&data[..]);
-
-    match result {
-      Ok((
-        _,
-        Program {
-          global_var_names,
-          const_table,
-          code,
-        },
-      )) => {
-        let mut machine = Machine::new(get_builtin(), global_var_names);
-        let frame = StackFrame {
-          code,
-          const_table,
-          slot_start: 0,
-          free_vars: Vec::new(),
-        };
-
-        match machine.run(&frame) {
-          Ok(_) => println!("정상 종료"),
-          Err((line_number, err)) => println!("{}번째 라인 에서 에러 발생 : {}", line_number, err),
-        }
-      }
-      Err(err) => {
-        println!("{:?}", err);
+fn exit_with(message: &str) -> ! {
+  println!("{}", message);
+  std::process::exit(1);
+}
+
+fn load_program(filename: &str) -> Program {
+  let data = match fs::read(filename) {
+    Ok(data) => data,
+    Err(_) => exit_with("파일을 찾을 수 없습니다."),
+  };
+
+  match program(&data[..]) {
+    Ok((_, parsed)) => parsed,
+    Err(err) => exit_with(&format!("{:?}", err)),
+  }
+}
+
+#[derive(Default)]
+struct Options {
+  filename: Option<String>,
+  trace: bool,
+  optimize: bool,
+  profile: bool,
+  profile_collapsed: Option<String>,
+  coverage: bool,
+  coverage_lcov: Option<String>,
+  allow_read: Vec<String>,
+  allow_write: Vec<String>,
+  module_path: Vec<String>,
+}
+
+fn parse_options(args: Vec<String>) -> Options {
+  let mut options = Options::default();
+
+  for arg in args {
+    let (name, value) = match arg.find('=') {
+      Some(index) => (&arg[..index], Some(arg[index + 1..].to_string())),
+      None => (&arg[..], None),
+    };
+
+    match (name, value) {
+      ("--trace", None) => options.trace = true,
+      ("--optimize", None) => options.optimize = true,
+      ("--profile", None) => options.profile = true,
+      ("--profile-collapsed", Some(path)) => options.profile_collapsed = Some(path),
+      ("--coverage", path) => {
+        options.coverage = true;
+        options.coverage_lcov = path;
       }
+      ("--allow-read", Some(dir)) => options.allow_read.push(dir),
+      ("--allow-write", Some(dir)) => options.allow_write.push(dir),
+      ("--module-path", Some(dir)) => options.module_path.push(dir),
+      _ if arg.starts_with("--") => exit_with(&format!("알 수 없는 옵션입니다 : {}", arg)),
+      _ if options.filename.is_none() => options.filename = Some(arg),
+      _ => exit_with("파일 이름은 하나만 입력해주세요."),
+    }
+  }
+
+  options
+}
+
+fn main() {
+  let mut args: Vec<String> = env::args().skip(1).collect();
+  if args.first().map(String::as_str) == Some("dap") {
+    let stdin = io::stdin();
+    if let Err(err) = DapServer::new(stdin.lock(), io::stdout()).run() {
+      eprintln!("디버그 서버 입출력 에러 : {}", err);
+      std::process::exit(1);
+    }
+    return;
+  }
+
+  if args.first().map(String::as_str) == Some("link") {
+    link_files(args.split_off(1));
+    return;
+  }
+
+  let debug = args.first().map(String::as_str) == Some("debug");
+  if debug {
+    args.remove(0);
+  }
+
+  let options = parse_options(args);
+  let filename = match &options.filename {
+    Some(filename) => filename,
+    None => exit_with("파일 이름을 입력해주세요."),
+  };
+
+  let mut program = load_program(filename);
+  if options.optimize {
+    program = optimize(program);
+  }
+  let permissions = permissions_of(&options);
+  let mut loader = ModuleLoader::new(search_path(filename, &options));
+  loader.set_permissions(permissions.clone());
+  let globals = match loader.globals(&mut program) {
+    Ok(globals) => globals,
+    Err(err) => exit_with(&err.to_string()),
+  };
+  let mut machine = Machine::new(
+    globals,
+    program.global_var_names.clone(),
+    program.josa_table.clone(),
+  );
+  machine.set_permissions(permissions);
+  if options.coverage {
+    machine.set_coverage(Some(Coverage::new(&program)));
+  }
+  let frame = StackFrame::new(program.code, program.const_table);
+
+  if options.trace {
+    machine.set_tracer(Some(Box::new(io::stderr())));
+  }
+  if options.profile || options.profile_collapsed.is_some() {
+    machine.set_profiler(Some(Profiler::new()));
+  }
+
+  if debug {
+    machine.load(frame);
+
+    let stdin = io::stdin();
+    let mut debugger = Debugger::new(machine, stdin.lock(), io::stdout());
+    if let Err(err) = debugger.run() {
+      exit_with(&format!("디버거 입출력 에러 : {}", err));
+    }
+    return;
+  }
+
+  let message = match machine.run(frame) {
+    Ok(_) => String::from("정상 종료\n"),
+    Err((line_number, err)) => format!("{}번째 라인 에서 에러 발생 : {}\n", line_number, err),
+  };
+  // 프로그램이 출력한 내용과 같은 곳으로 내보냅니다.
+  let _ = machine.output().write_str(&message);
+
+  if let Some(profiler) = machine.take_profiler() {
+    write_profile(&profiler, &options);
+  }
+
+  if let Some(coverage) = machine.take_coverage() {
+    write_coverage(&coverage, filename, &options);
+  }
+}
+
+/// `haneul link --output=결과.hnc 파일...`처럼 여러 파일을 하나로 합쳐 씁니다. 모듈 이름은 파일 이름에서 확장자를 뺀 것입니다.
+fn link_files(args: Vec<String>) {
+  let mut output = None;
+  let mut units = Vec::new();
+  for arg in args {
+    if let Some(path) = arg.strip_prefix("--output=") {
+      output = Some(path.to_string());
+    } else if arg.starts_with("--") {
+      exit_with(&format!("알 수 없는 옵션입니다 : {}", arg));
+    } else {
+      let name = Path::new(&arg)
+        .file_stem()
+        .map(|x| x.to_string_lossy().into_owned());
+      units.push(LinkUnit {
+        name: name.unwrap_or_default(),
+        program: load_program(&arg),
+      });
+    }
+  }
+
+  let output = match output {
+    Some(output) => output,
+    None => exit_with("결과를 쓸 파일을 --output으로 입력해주세요."),
+  };
+  if units.is_empty() {
+    exit_with("링크할 파일 이름을 입력해주세요.");
+  }
+
+  let linked = match link(units) {
+    Ok(linked) => linked,
+    Err(err) => exit_with(&err.to_string()),
+  };
+  let bytes = match encode(&linked) {
+    Ok(bytes) => bytes,
+    Err(err) => exit_with(&err.to_string()),
+  };
+  if let Err(err) = fs::write(&output, bytes) {
+    exit_with(&format!(
+      "'{}'에 링크 결과를 쓸 수 없습니다 : {}",
+      output, err
+    ));
+  }
+}
+
+/// 실행하는 파일이 있는 디렉터리에서 먼저 모듈을 찾고, 그 다음 `--module-path`로 준 디렉터리에서 찾습니다.
+fn search_path(filename: &str, options: &Options) -> Vec<PathBuf> {
+  let base = Path::new(filename).parent().map(Path::to_path_buf);
+  base
+    .into_iter()
+    .chain(options.module_path.iter().map(PathBuf::from))
+    .collect()
+}
+
+fn permissions_of(options: &Options) -> Permissions {
+  let mut permissions = Permissions::new();
+  for dir in &options.allow_read {
+    if let Err(err) = permissions.allow_read(dir) {
+      exit_with(&format!("'{}'에 읽기 권한을 줄 수 없습니다 : {}", dir, err));
+    }
+  }
+  for dir in &options.allow_write {
+    if let Err(err) = permissions.allow_write(dir) {
+      exit_with(&format!("'{}'에 쓰기 권한을 줄 수 없습니다 : {}", dir, err));
+    }
+  }
+  permissions
+}
+
+fn write_profile(profiler: &Profiler, options: &Options) {
+  if options.profile {
+    if let Err(err) = profiler.write_report(&mut io::stderr()) {
+      exit_with(&format!("프로파일 결과를 출력할 수 없습니다 : {}", err));
+    }
+  }
+
+  if let Some(path) = &options.profile_collapsed {
+    let result = File::create(path).and_then(|mut file| profiler.write_collapsed(&mut file));
+    if let Err(err) = result {
+      exit_with(&format!(
+        "'{}'에 프로파일 결과를 쓸 수 없습니다 : {}",
+        path, err
+      ));
+    }
+  }
+}
+
+fn write_coverage(coverage: &Coverage, filename: &str, options: &Options) {
+  if let Err(err) = coverage.write_summary(&mut io::stderr()) {
+    exit_with(&format!("커버리지 결과를 출력할 수 없습니다 : {}", err));
+  }
+
+  if let Some(path) = &options.coverage_lcov {
+    let result = File::create(path).and_then(|mut file| coverage.write_lcov(&mut file, filename));
+    if let Err(err) = result {
+      exit_with(&format!(
+        "'{}'에 커버리지 결과를 쓸 수 없습니다 : {}",
+        path, err
+      ));
     }
-  } else {
-    println!("파일을 찾을 수 없습니다.");
   }
 }
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::BufRead;
+use std::mem;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::builtin::get_builtin;
+use crate::constant::Constant;
+use crate::error::HaneulError;
+use crate::io::{Io, Output};
+use crate::josa::JosaTable;
+use crate::machine::{Machine, MemoryLimits, StackFrame};
+use crate::parser::program_with_josa_table;
+use crate::permission::Permissions;
+use crate::program::Program;
+use crate::relocation::Relocation;
+
+/// 모듈이 내보낸 전역 변수의 이름과 값입니다.
+pub type Exports = HashMap<String, Constant>;
+
+/// 프로그램이 가져오는 모듈을 찾아 실행합니다. 모듈 `이름`은 검색 경로의 디렉터리에서 `이름.hnc` 파일을 찾으며,
+/// 모듈마다 한 번만 실행하고 내보낸 값을 기억해 두었다가 다시 가져갈 때 그대로 돌려줍니다.
+/// 모듈의 최상위 코드는 불러오는 쪽이 정한 입출력과 실행 한도로 실행합니다.
+///
+/// 모듈의 전역 변수는 `모듈::이름`이라는 이름으로 모든 모듈이 함께 쓰는 전역 변수 테이블에 두고, 모듈의 코드는
+/// 실행하기 전에 이 테이블의 번호를 쓰도록 바꿉니다. 그래서 모듈이 내보낸 함수는 어느 프로그램에서 불러도
+/// 자기 모듈의 전역 변수를 읽고 씁니다.
+#[derive(Default)]
+pub struct ModuleLoader {
+  search_path: Vec<PathBuf>,
+  io: Io,
+  fuel: Option<u64>,
+  deadline: Option<Instant>,
+  memory_limits: MemoryLimits,
+  /// 모든 모듈과 프로그램이 함께 쓰는 조사 테이블입니다. 기억해 둔 모듈의 값을 어느 프로그램에 주어도 조사 번호가 맞습니다.
+  josa_table: JosaTable,
+  cache: HashMap<String, Rc<Exports>>,
+  /// 불러온 모듈들의 전역 변수 이름과 값입니다.
+  global_var_names: Vec<String>,
+  global_vars: Vec<Option<Constant>>,
+  /// 지금 불러오고 있는 모듈들입니다. 가져오는 순환을 찾는 데 씁니다.
+  loading: Vec<String>,
+}
+
+fn module_failed(module: &str, reason: String) -> HaneulError {
+  HaneulError::ModuleFailed {
+    module: module.to_string(),
+    reason,
+  }
+}
+
+impl ModuleLoader {
+  pub fn new(search_path: Vec<PathBuf>) -> ModuleLoader {
+    ModuleLoader {
+      search_path,
+      ..Default::default()
+    }
+  }
+
+  /// 모듈의 최상위 코드를 실행할 때 쓰는 파일 접근 권한입니다. 기본값은 아무 곳에도 접근할 수 없습니다.
+  pub fn set_permissions(&mut self, permissions: Permissions) {
+    self.io.set_permissions(permissions);
+  }
+
+  /// 모듈이 출력하는 곳입니다. 기본값은 표준 출력입니다.
+  pub fn set_output(&mut self, output: Box<dyn Output>) {
+    self.io.set_output(output);
+  }
+
+  /// 모듈이 입력을 읽는 곳입니다. 기본값은 표준 입력입니다.
+  pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+    self.io.set_input(input);
+  }
+
+  /// 모듈들이 함께 쓰는 명령어 실행 한도입니다. 모듈을 실행할 때마다 쓴 만큼 줄어듭니다.
+  pub fn set_fuel(&mut self, fuel: Option<u64>) {
+    self.fuel = fuel;
+  }
+
+  /// 모듈을 실행하고 남은 명령어 실행 한도입니다.
+  pub fn fuel(&self) -> Option<u64> {
+    self.fuel
+  }
+
+  pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+    self.deadline = deadline;
+  }
+
+  pub fn set_memory_limits(&mut self, limits: MemoryLimits) {
+    self.memory_limits = limits;
+  }
+
+  /// `program`이 가져오는 모듈을 불러와 실행하고, 내장 함수와 가져온 값을 채운 전역 변수 목록을 만듭니다.
+  /// 전역 변수 목록의 앞에는 불러온 모듈들의 전역 변수가 오므로 `program`의 전역 변수 번호가 그만큼 밀리고,
+  /// 조사 번호는 불러오는 쪽의 조사 테이블에 맞춰 바뀝니다. 가상 머신은 바뀐 `program.global_var_names`와
+  /// `program.josa_table`로 만들어야 합니다.
+  pub fn globals(&mut self, program: &mut Program) -> Result<Vec<Option<Constant>>, HaneulError> {
+    let vars = self.bind(program)?;
+    program.global_var_names = self
+      .global_var_names
+      .iter()
+      .chain(&program.global_var_names)
+      .cloned()
+      .collect();
+    Ok(self.global_vars.iter().cloned().chain(vars).collect())
+  }
+
+  /// `program`이 가져오는 모듈을 불러오고, 프로그램의 전역 변수가 모듈들의 전역 변수 뒤에 오도록 번호를 바꿉니다.
+  /// 프로그램의 전역 변수마다 내장 함수나 가져온 값을 채워 돌려줍니다.
+  fn bind(&mut self, program: &mut Program) -> Result<Vec<Option<Constant>>, HaneulError> {
+    let mut imported = Vec::new();
+    for import in &program.imports {
+      let exports = self.load(&import.module)?;
+      for name in &import.names {
+        let value = exports
+          .get(name)
+          .ok_or_else(|| HaneulError::MissingExport {
+            module: import.module.clone(),
+            name: name.clone(),
+          })?;
+        imported.push((name.clone(), value.clone()));
+      }
+    }
+
+    let offset = self.global_var_names.len() as u32;
+    let relocation = Relocation {
+      globals: (offset..offset + program.global_var_names.len() as u32).collect(),
+      josa: program
+        .josa_table
+        .names()
+        .iter()
+        .map(|name| self.josa_table.intern(name))
+        .collect(),
+    };
+    relocation.program(program);
+
+    let mut vars = get_builtin(&mut self.josa_table, &program.global_var_names);
+    program.josa_table = self.josa_table.clone();
+    for (name, value) in imported {
+      if let Some(index) = program.global_var_names.iter().position(|x| *x == name) {
+        vars[index] = Some(value);
+      }
+    }
+    Ok(vars)
+  }
+
+  fn load(&mut self, module: &str) -> Result<Rc<Exports>, HaneulError> {
+    if let Some(exports) = self.cache.get(module) {
+      return Ok(exports.clone());
+    }
+    if let Some(start) = self.loading.iter().position(|x| x == module) {
+      let mut cycle = self.loading[start..].to_vec();
+      cycle.push(module.to_string());
+      return Err(HaneulError::ImportCycle { cycle });
+    }
+
+    let path = self
+      .search_path
+      .iter()
+      .map(|dir| dir.join(format!("{}.hnc", module)))
+      .find(|path| path.is_file())
+      .ok_or_else(|| HaneulError::ModuleNotFound {
+        module: module.to_string(),
+      })?;
+    let data = fs::read(&path).map_err(|err| module_failed(module, err.to_string()))?;
+    let mut program = match program_with_josa_table(&data[..], self.josa_table.clone()) {
+      Ok((_, program)) => program,
+      Err(err) => return Err(module_failed(module, format!("{:?}", err))),
+    };
+
+    self.loading.push(module.to_string());
+    let exports = self.run(module, &mut program);
+    self.loading.pop();
+
+    let exports = Rc::new(exports?);
+    self.cache.insert(module.to_string(), exports.clone());
+    Ok(exports)
+  }
+
+  fn run(&mut self, module: &str, program: &mut Program) -> Result<Exports, HaneulError> {
+    let vars = self.bind(program)?;
+    let offset = self.global_var_names.len();
+    self.global_var_names.extend(
+      program
+        .global_var_names
+        .iter()
+        .map(|name| format!("{}::{}", module, name)),
+    );
+    self.global_vars.extend(vars);
+
+    let mut machine = Machine::new(
+      self.global_vars.clone(),
+      self.global_var_names.clone(),
+      program.josa_table.clone(),
+    );
+    machine.set_fuel(self.fuel);
+    machine.set_deadline(self.deadline);
+    machine.set_memory_limits(self.memory_limits);
+    machine.replace_io(mem::take(&mut self.io));
+
+    let frame = StackFrame::new(program.code.clone(), program.const_table.clone());
+    let result = machine.run(frame);
+    self.io = machine.replace_io(Io::default());
+    self.fuel = machine.fuel();
+    result.map_err(|(line_number, err)| {
+      module_failed(
+        module,
+        format!("{}번째 라인 에서 에러 발생 : {}", line_number, err),
+      )
+    })?;
+    self.global_vars = machine.global_vars().to_vec();
+
+    program
+      .exports
+      .iter()
+      .map(|name| {
+        let index = program.global_var_names.iter().position(|x| x == name);
+        match index.and_then(|index| self.global_vars[offset + index].as_ref()) {
+          Some(value) => Ok((name.clone(), value.clone())),
+          None => Err(HaneulError::MissingExport {
+            module: module.to_string(),
+            name: name.clone(),
+          }),
+        }
+      })
+      .collect()
+  }
+}
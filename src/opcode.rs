@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
 
+use crate::josa::JosaId;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum BinaryOp {
   Add,
@@ -44,7 +46,7 @@ pub enum Opcode {
   LoadDeref(u32),
   StoreGlobal(u32),
   LoadGlobal(u32),
-  Call(Vec<String>),
+  Call(Vec<JosaId>),
   Jmp(u32),
   PopJmpIfFalse(u32),
   FreeVarLocal(u8),
@@ -52,3 +54,31 @@ pub enum Opcode {
   BinaryOp(BinaryOp),
   UnaryOp(UnaryOp),
 }
+
+impl Opcode {
+  /// 프로파일러 등에서 명령어의 종류를 구분할 때 쓰는 이름입니다.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Opcode::Push(_) => "Push",
+      Opcode::Pop => "Pop",
+      Opcode::Load(_) => "Load",
+      Opcode::LoadDeref(_) => "LoadDeref",
+      Opcode::StoreGlobal(_) => "StoreGlobal",
+      Opcode::LoadGlobal(_) => "LoadGlobal",
+      Opcode::Call(_) => "Call",
+      Opcode::Jmp(_) => "Jmp",
+      Opcode::PopJmpIfFalse(_) => "PopJmpIfFalse",
+      Opcode::FreeVarLocal(_) => "FreeVarLocal",
+      Opcode::FreeVarFree(_) => "FreeVarFree",
+      Opcode::BinaryOp(BinaryOp::Add) => "Add",
+      Opcode::BinaryOp(BinaryOp::Subtract) => "Subtract",
+      Opcode::BinaryOp(BinaryOp::Multiply) => "Multiply",
+      Opcode::BinaryOp(BinaryOp::Divide) => "Divide",
+      Opcode::BinaryOp(BinaryOp::Mod) => "Mod",
+      Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Equal)) => "Equal",
+      Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Less)) => "LessThan",
+      Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Greater)) => "GreaterThan",
+      Opcode::UnaryOp(UnaryOp::Negate) => "Negate",
+    }
+  }
+}
@@ -0,0 +1,450 @@
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use crate::constant::Constant;
+use crate::funcobject::FuncObject;
+use crate::instruction::Instruction;
+use crate::opcode::{BinaryOp, Opcode, UnaryOp};
+use crate::program::Program;
+
+/// 프로그램과 그 안의 모든 코드 객체에 핍홀 최적화와 상수 접기를 적용합니다.
+/// 에러를 낼 수 있는 명령어는 원래의 라인 번호를 그대로 유지합니다.
+pub fn optimize(program: Program) -> Program {
+  let (code, const_table) = optimize_code(program.code, program.const_table);
+
+  Program {
+    const_table,
+    code,
+    global_var_names: program.global_var_names,
+    josa_table: program.josa_table,
+    imports: program.imports,
+    exports: program.exports,
+  }
+}
+
+fn optimize_code(
+  mut code: Vec<Instruction>,
+  const_table: Vec<Constant>,
+) -> (Vec<Instruction>, Vec<Constant>) {
+  let mut const_table: Vec<Constant> = const_table.into_iter().map(optimize_constant).collect();
+
+  loop {
+    let mut changed = false;
+    changed |= fold_constants(&mut code, &mut const_table);
+    changed |= thread_jumps(&mut code);
+    changed |= remove_unreachable(&mut code);
+
+    if !changed {
+      break;
+    }
+  }
+
+  let const_table = remove_unused_constants(&mut code, const_table);
+  (code, const_table)
+}
+
+fn optimize_constant(value: Constant) -> Constant {
+  match value {
+    Constant::Function {
+      josa_map,
+      rest_args,
+      func_object:
+        FuncObject::CodeObject {
+          code,
+          const_table,
+          free_vars,
+          ..
+        },
+    } => {
+      let (code, const_table) =
+        optimize_code(Rc::unwrap_or_clone(code), Rc::unwrap_or_clone(const_table));
+      Constant::Function {
+        josa_map,
+        rest_args,
+        func_object: FuncObject::CodeObject {
+          code: Rc::new(code),
+          const_table: Rc::new(const_table),
+          free_vars,
+          caches: Rc::default(),
+        },
+      }
+    }
+    value => value,
+  }
+}
+
+fn jump_target(opcode: &Opcode) -> Option<usize> {
+  match opcode {
+    Opcode::Jmp(v) | Opcode::PopJmpIfFalse(v) => Some(*v as usize),
+    _ => None,
+  }
+}
+
+fn jump_targets(code: &[Instruction]) -> BTreeSet<usize> {
+  code
+    .iter()
+    .filter_map(|inst| jump_target(&inst.opcode))
+    .collect()
+}
+
+/// 정수 연산은 넘침이나 0으로 나누기가 없을 때만 접어서, 그런 경우는 실행 중에 그대로 일어나게 둡니다.
+fn fold_binary(op: &BinaryOp, lhs: &Constant, rhs: &Constant) -> Option<Constant> {
+  if let (Constant::Integer(lhs), Constant::Integer(rhs)) = (lhs, rhs) {
+    let result = match op {
+      BinaryOp::Add => lhs.checked_add(*rhs),
+      BinaryOp::Subtract => lhs.checked_sub(*rhs),
+      BinaryOp::Multiply => lhs.checked_mul(*rhs),
+      BinaryOp::Divide => lhs.checked_div(*rhs),
+      BinaryOp::Mod => lhs.checked_rem(*rhs),
+      BinaryOp::Cmp(ord) => return Some(Constant::Boolean(lhs.cmp(rhs) == *ord)),
+    };
+    return result.map(Constant::Integer);
+  }
+
+  match op {
+    BinaryOp::Add => lhs + rhs,
+    BinaryOp::Subtract => lhs - rhs,
+    BinaryOp::Multiply => lhs * rhs,
+    BinaryOp::Divide => lhs / rhs,
+    BinaryOp::Mod => lhs % rhs,
+    BinaryOp::Cmp(ord) => PartialOrd::partial_cmp(lhs, rhs).map(|v| Constant::Boolean(v == *ord)),
+  }
+}
+
+fn fold_unary(op: &UnaryOp, value: &Constant) -> Option<Constant> {
+  match (op, value) {
+    (UnaryOp::Negate, Constant::Integer(v)) => v.checked_neg().map(Constant::Integer),
+    (UnaryOp::Negate, value) => -value,
+  }
+}
+
+/// `0.0`과 `-0.0`처럼 `==`로는 같지만 다르게 동작하는 값을 구분하여 비교합니다.
+pub(crate) fn same_constant(a: &Constant, b: &Constant) -> bool {
+  match (a, b) {
+    (Constant::None, Constant::None) => true,
+    (Constant::Integer(a), Constant::Integer(b)) => a == b,
+    (Constant::Real(a), Constant::Real(b)) => a.to_bits() == b.to_bits(),
+    (Constant::Char(a), Constant::Char(b)) => a == b,
+    (Constant::Boolean(a), Constant::Boolean(b)) => a == b,
+    (Constant::Str(a), Constant::Str(b)) => a == b,
+    _ => false,
+  }
+}
+
+fn add_constant(const_table: &mut Vec<Constant>, value: Constant) -> u32 {
+  match const_table.iter().position(|x| same_constant(x, &value)) {
+    Some(index) => index as u32,
+    None => {
+      const_table.push(value);
+      (const_table.len() - 1) as u32
+    }
+  }
+}
+
+/// 상수만으로 이루어진 연산을 미리 계산하고, 결과가 정해진 분기와 바로 버려지는 값을 없앱니다.
+fn fold_constants(code: &mut Vec<Instruction>, const_table: &mut Vec<Constant>) -> bool {
+  let targets = jump_targets(code);
+  let mut removed = vec![false; code.len()];
+  let mut changed = false;
+
+  // 패턴의 중간으로 점프해 들어오는 경우에는 합칠 수 없습니다.
+  let code_len = code.len();
+  let is_block = |start: usize, len: usize| {
+    start + len <= code_len && (start + 1..start + len).all(|x| !targets.contains(&x))
+  };
+
+  let mut i = 0;
+  while i < code.len() {
+    if is_block(i, 3) {
+      if let (Opcode::Push(lhs), Opcode::Push(rhs), Opcode::BinaryOp(op)) =
+        (&code[i].opcode, &code[i + 1].opcode, &code[i + 2].opcode)
+      {
+        let result = fold_binary(op, &const_table[*lhs as usize], &const_table[*rhs as usize]);
+        if let Some(result) = result {
+          let index = add_constant(const_table, result);
+          code[i] = Instruction {
+            line_number: code[i + 2].line_number,
+            opcode: Opcode::Push(index),
+          };
+          removed[i + 1] = true;
+          removed[i + 2] = true;
+          changed = true;
+          i += 3;
+          continue;
+        }
+      }
+    }
+
+    if is_block(i, 2) {
+      match (&code[i].opcode, &code[i + 1].opcode) {
+        (Opcode::Push(v), Opcode::UnaryOp(op)) => {
+          if let Some(result) = fold_unary(op, &const_table[*v as usize]) {
+            let index = add_constant(const_table, result);
+            code[i] = Instruction {
+              line_number: code[i + 1].line_number,
+              opcode: Opcode::Push(index),
+            };
+            removed[i + 1] = true;
+            changed = true;
+            i += 2;
+            continue;
+          }
+        }
+        (Opcode::Push(v), Opcode::PopJmpIfFalse(target)) => {
+          if let Constant::Boolean(value) = const_table[*v as usize] {
+            if value {
+              removed[i] = true;
+            } else {
+              code[i] = Instruction {
+                line_number: code[i + 1].line_number,
+                opcode: Opcode::Jmp(*target),
+              };
+            }
+            removed[i + 1] = true;
+            changed = true;
+            i += 2;
+            continue;
+          }
+        }
+        (Opcode::Push(_), Opcode::Pop)
+        | (Opcode::Load(_), Opcode::Pop)
+        | (Opcode::LoadDeref(_), Opcode::Pop) => {
+          removed[i] = true;
+          removed[i + 1] = true;
+          changed = true;
+          i += 2;
+          continue;
+        }
+        _ => {}
+      }
+    }
+
+    i += 1;
+  }
+
+  if changed {
+    remove_instructions(code, &removed);
+  }
+  changed
+}
+
+/// 점프할 곳이 다시 무조건 점프라면 최종 목적지로 바로 점프하고, 바로 다음 명령어로 가는 점프는 없앱니다.
+fn thread_jumps(code: &mut Vec<Instruction>) -> bool {
+  let mut removed = vec![false; code.len()];
+  let mut changed = false;
+
+  for i in 0..code.len() {
+    let target = match jump_target(&code[i].opcode) {
+      Some(target) => target,
+      None => continue,
+    };
+
+    let mut final_target = target;
+    let mut hops = 0;
+    while let Some(Opcode::Jmp(next)) = code.get(final_target).map(|x| &x.opcode) {
+      // 자기 자신으로 돌아오는 점프 사슬에서 멈추지 않는 것을 막습니다.
+      if hops > code.len() {
+        break;
+      }
+      final_target = *next as usize;
+      hops += 1;
+    }
+
+    match &mut code[i].opcode {
+      Opcode::Jmp(v) | Opcode::PopJmpIfFalse(v) if final_target != target => {
+        *v = final_target as u32;
+        changed = true;
+      }
+      _ => {}
+    }
+
+    if let Opcode::Jmp(v) = code[i].opcode {
+      if v as usize == i + 1 {
+        removed[i] = true;
+        changed = true;
+      }
+    }
+  }
+
+  if removed.contains(&true) {
+    remove_instructions(code, &removed);
+  }
+  changed
+}
+
+/// 첫 명령어에서 도달할 수 없는 명령어를 없앱니다.
+fn remove_unreachable(code: &mut Vec<Instruction>) -> bool {
+  let mut reachable = vec![false; code.len()];
+  let mut pending = vec![0];
+
+  while let Some(i) = pending.pop() {
+    if i >= code.len() || reachable[i] {
+      continue;
+    }
+    reachable[i] = true;
+
+    match code[i].opcode {
+      Opcode::Jmp(v) => pending.push(v as usize),
+      Opcode::PopJmpIfFalse(v) => {
+        pending.push(v as usize);
+        pending.push(i + 1);
+      }
+      _ => pending.push(i + 1),
+    }
+  }
+
+  let removed: Vec<bool> = reachable.iter().map(|x| !x).collect();
+  if removed.contains(&true) {
+    remove_instructions(code, &removed);
+    true
+  } else {
+    false
+  }
+}
+
+/// 표시된 명령어를 지우고 점프 위치를 다시 계산합니다. 지워진 명령어로 향하던 점프는
+/// 그 다음에 남아 있는 명령어로 향합니다.
+fn remove_instructions(code: &mut Vec<Instruction>, removed: &[bool]) {
+  let mut new_index = Vec::with_capacity(code.len() + 1);
+  let mut count = 0;
+  for is_removed in removed {
+    new_index.push(count as u32);
+    if !is_removed {
+      count += 1;
+    }
+  }
+  new_index.push(count as u32);
+
+  let old_code = std::mem::take(code);
+  *code = old_code
+    .into_iter()
+    .zip(removed)
+    .filter(|(_, is_removed)| !**is_removed)
+    .map(|(mut inst, _)| {
+      match &mut inst.opcode {
+        Opcode::Jmp(v) | Opcode::PopJmpIfFalse(v) => *v = new_index[*v as usize],
+        _ => {}
+      }
+      inst
+    })
+    .collect();
+}
+
+fn remove_unused_constants(code: &mut [Instruction], const_table: Vec<Constant>) -> Vec<Constant> {
+  let mut new_index = vec![None; const_table.len()];
+  let mut new_table = Vec::new();
+
+  for inst in code.iter_mut() {
+    if let Opcode::Push(v) = &mut inst.opcode {
+      let index = *new_index[*v as usize].get_or_insert_with(|| {
+        new_table.push(const_table[*v as usize].clone());
+        new_table.len() as u32 - 1
+      });
+      *v = index;
+    }
+  }
+
+  new_table
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::cmp::Ordering;
+
+  use crate::josa::JosaTable;
+
+  fn inst(line_number: u32, opcode: Opcode) -> Instruction {
+    Instruction {
+      line_number,
+      opcode,
+    }
+  }
+
+  #[test]
+  fn fold_and_simplify() {
+    // 1 + 2 < 4 이면 결과 = -(3) 아니면 결과 = 0 , 그리고 사용하지 않는 상수 10
+    let program = Program {
+      const_table: vec![
+        Constant::Integer(1),
+        Constant::Integer(2),
+        Constant::Integer(4),
+        Constant::Integer(3),
+        Constant::Integer(0),
+        Constant::Integer(10),
+      ],
+      code: vec![
+        inst(1, Opcode::Push(0)),
+        inst(1, Opcode::Push(1)),
+        inst(1, Opcode::BinaryOp(BinaryOp::Add)),
+        inst(1, Opcode::Push(2)),
+        inst(1, Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Less))),
+        inst(1, Opcode::PopJmpIfFalse(10)),
+        inst(2, Opcode::Push(3)),
+        inst(2, Opcode::UnaryOp(UnaryOp::Negate)),
+        inst(2, Opcode::StoreGlobal(0)),
+        inst(2, Opcode::Jmp(12)),
+        inst(3, Opcode::Push(4)),
+        inst(3, Opcode::StoreGlobal(0)),
+        inst(4, Opcode::Jmp(13)),
+        inst(4, Opcode::Push(5)),
+        inst(4, Opcode::Pop),
+      ],
+      global_var_names: vec![String::from("결과")],
+      josa_table: JosaTable::new(),
+      imports: Vec::new(),
+      exports: Vec::new(),
+    };
+
+    let optimized = optimize(program);
+    assert_eq!(optimized.const_table, vec![Constant::Integer(-3)]);
+    assert_eq!(
+      optimized.code,
+      vec![inst(2, Opcode::Push(0)), inst(2, Opcode::StoreGlobal(0)),]
+    );
+  }
+
+  #[test]
+  fn keep_runtime_errors() {
+    let program = Program {
+      const_table: vec![
+        Constant::Integer(1),
+        Constant::Integer(0),
+        Constant::Boolean(true),
+      ],
+      code: vec![
+        inst(1, Opcode::Push(0)),
+        inst(1, Opcode::Push(1)),
+        inst(1, Opcode::BinaryOp(BinaryOp::Divide)),
+        inst(2, Opcode::Push(2)),
+        inst(2, Opcode::Push(0)),
+        inst(2, Opcode::BinaryOp(BinaryOp::Add)),
+      ],
+      global_var_names: Vec::new(),
+      josa_table: JosaTable::new(),
+      imports: Vec::new(),
+      exports: Vec::new(),
+    };
+
+    let optimized = optimize(program);
+    assert_eq!(optimized.code.len(), 6);
+    assert_eq!(optimized.code[2].line_number, 1);
+    assert_eq!(optimized.code[5].line_number, 2);
+  }
+
+  #[test]
+  fn thread_jump_chains() {
+    let mut code = vec![
+      inst(1, Opcode::Push(0)),
+      inst(1, Opcode::PopJmpIfFalse(3)),
+      inst(2, Opcode::Jmp(0)),
+      inst(3, Opcode::Jmp(4)),
+      inst(4, Opcode::Jmp(0)),
+    ];
+
+    assert!(thread_jumps(&mut code));
+    assert_eq!(code[1].opcode, Opcode::PopJmpIfFalse(0));
+    assert_eq!(code[3].opcode, Opcode::Jmp(0));
+  }
+}
@@ -1,16 +1,18 @@
 extern crate nom;
 
+use std::cell::RefCell;
 use std::char;
 use std::cmp::Ordering;
+use std::rc::Rc;
 
-use indexmap::IndexMap;
 use nom::{combinator, multi, number::complete::*, IResult};
 
 use crate::constant::Constant;
 use crate::funcobject::FuncObject;
 use crate::instruction::Instruction;
+use crate::josa::{JosaMap, JosaTable};
 use crate::opcode::{BinaryOp, Opcode, UnaryOp};
-use crate::program::Program;
+use crate::program::{Import, Program};
 
 fn integer(input: &[u8]) -> IResult<&[u8], i64> {
   be_i64(input)
@@ -74,7 +76,10 @@ fn apply<A, B, C>(value: (A, B), f: fn(B) -> C) -> (A, C) {
   (a, f(b))
 }
 
-fn instruction(input: &[u8]) -> IResult<&[u8], Instruction> {
+fn instruction<'a>(
+  input: &'a [u8],
+  josa_table: &RefCell<JosaTable>,
+) -> IResult<&'a [u8], Instruction> {
   let (input, line_number) = be_u32(input)?;
   let (input, opcode_index) = be_u8(input)?;
   let (input, opcode) = match opcode_index {
@@ -84,7 +89,12 @@ fn instruction(input: &[u8]) -> IResult<&[u8], Instruction> {
     3 => apply(be_u32(input)?, Opcode::LoadDeref),
     4 => apply(be_u32(input)?, Opcode::StoreGlobal),
     5 => apply(be_u32(input)?, Opcode::LoadGlobal),
-    6 => apply(list_u8(input, string_u8)?, Opcode::Call),
+    6 => {
+      let (input, josa_list) = list_u8(input, string_u8)?;
+      let mut josa_table = josa_table.borrow_mut();
+      let josa_list = josa_list.iter().map(|josa| josa_table.intern(josa));
+      (input, Opcode::Call(josa_list.collect()))
+    }
     7 => apply(be_u32(input)?, Opcode::Jmp),
     8 => apply(be_u32(input)?, Opcode::PopJmpIfFalse),
     9 => apply(be_u8(input)?, Opcode::FreeVarLocal),
@@ -110,21 +120,48 @@ fn instruction(input: &[u8]) -> IResult<&[u8], Instruction> {
   ))
 }
 
-fn code_object(input: &[u8]) -> IResult<&[u8], FuncObject> {
-  let (input, const_table) = list(input, constant)?;
-  let (input, code) = list(input, instruction)?;
+fn code_object<'a>(
+  input: &'a [u8],
+  josa_table: &RefCell<JosaTable>,
+) -> IResult<&'a [u8], FuncObject> {
+  let (input, const_table) = list(input, |x| constant(x, josa_table))?;
+  let (input, code) = list(input, |x| instruction(x, josa_table))?;
 
   Ok((
     input,
     FuncObject::CodeObject {
-      const_table,
-      code,
-      free_vars: Vec::new(),
+      const_table: Rc::new(const_table),
+      code: Rc::new(code),
+      free_vars: Rc::default(),
+      caches: Rc::default(),
+    },
+  ))
+}
+
+fn function<'a>(
+  input: &'a [u8],
+  variadic: bool,
+  josa_table: &RefCell<JosaTable>,
+) -> IResult<&'a [u8], Constant> {
+  let (input, josa_list) = list_u8(input, string_u8)?;
+  let (input, value) = code_object(input, josa_table)?;
+
+  let josa_map = {
+    let mut josa_table = josa_table.borrow_mut();
+    JosaMap::new(josa_list.iter().map(|josa| josa_table.intern(josa)))
+  };
+
+  Ok((
+    input,
+    Constant::Function {
+      josa_map: Rc::new(josa_map),
+      rest_args: if variadic { Some(Rc::default()) } else { None },
+      func_object: value,
     },
   ))
 }
 
-fn constant(input: &[u8]) -> IResult<&[u8], Constant> {
+fn constant<'a>(input: &'a [u8], josa_table: &RefCell<JosaTable>) -> IResult<&'a [u8], Constant> {
   let (input, constant_index) = be_u8(input)?;
   let (input, constant) = match constant_index {
     0 => (input, Constant::None),
@@ -144,32 +181,43 @@ fn constant(input: &[u8]) -> IResult<&[u8], Constant> {
       let (input, value) = boolean(input)?;
       (input, Constant::Boolean(value))
     }
-    5 => {
-      let (input, josa_list) = list_u8(input, string_u8)?;
-      let (input, value) = code_object(input)?;
-
-      let mut josa_map = IndexMap::new();
-      for josa in josa_list {
-        josa_map.insert(josa, None);
-      }
-
-      (
-        input,
-        Constant::Function {
-          josa_map,
-          func_object: value,
-        },
-      )
+    5 => function(input, false, josa_table)?,
+    6 => function(input, true, josa_table)?,
+    7 => {
+      let (input, value) = string(input)?;
+      (input, Constant::Str(value.into()))
     }
     _ => panic!("invalid constant type value"),
   };
   Ok((input, constant))
 }
 
+fn import(input: &[u8]) -> IResult<&[u8], Import> {
+  let (input, module) = string(input)?;
+  let (input, names) = list(input, string)?;
+  Ok((input, Import { module, names }))
+}
+
 pub fn program(input: &[u8]) -> IResult<&[u8], Program> {
+  program_with_josa_table(input, JosaTable::new())
+}
+
+/// `josa_table`에 이어서 조사를 등록하며 읽습니다. 이미 등록된 조사는 번호가 바뀌지 않으므로,
+/// 여러 모듈을 같은 테이블로 읽으면 모듈 사이에 함수를 주고받을 수 있습니다.
+pub fn program_with_josa_table(input: &[u8], josa_table: JosaTable) -> IResult<&[u8], Program> {
   let (input, global_var_names) = list(input, string)?;
-  let (input, const_table) = list(input, constant)?;
-  let (input, code) = list(input, instruction)?;
+  let josa_table = RefCell::new(josa_table);
+  let (input, const_table) = list(input, |x| constant(x, &josa_table))?;
+  let (input, code) = list(input, |x| instruction(x, &josa_table))?;
+
+  // 모듈 정보는 파일 끝에 붙는 선택 항목입니다. 없으면 가져오거나 내보내는 것이 없습니다.
+  let (input, imports, exports) = if input.is_empty() {
+    (input, Vec::new(), Vec::new())
+  } else {
+    let (input, imports) = list(input, import)?;
+    let (input, exports) = list(input, string)?;
+    (input, imports, exports)
+  };
 
   Ok((
     input,
@@ -177,6 +225,9 @@ pub fn program(input: &[u8]) -> IResult<&[u8], Program> {
       global_var_names,
       const_table,
       code,
+      josa_table: josa_table.into_inner(),
+      imports,
+      exports,
     },
   ))
 }
@@ -185,6 +236,8 @@ pub fn program(input: &[u8]) -> IResult<&[u8], Program> {
 mod tests {
   use super::*;
 
+  use crate::josa::{JosaId, BLANK};
+
   #[test]
   fn parse_integer() {
     assert_eq!(
@@ -253,10 +306,32 @@ mod tests {
         );
   }
 
+  #[test]
+  fn parse_module_info() {
+    let empty = [0u8; 24];
+    let (_, parsed) = program(&empty).unwrap();
+    assert!(parsed.imports.is_empty() && parsed.exports.is_empty());
+
+    let mut data = empty.to_vec();
+    data.extend(b"\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x01a");
+    data.extend(b"\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x01x");
+    data.extend(b"\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x01y");
+    let (rest, parsed) = program(&data).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+      parsed.imports,
+      vec![Import {
+        module: String::from("a"),
+        names: vec![String::from("x")],
+      }]
+    );
+    assert_eq!(parsed.exports, vec![String::from("y")]);
+  }
+
   #[test]
   fn parse_instruction() {
     assert_eq!(
-      instruction(b"\x00\x00\x00\x0a\x01"),
+      instruction(b"\x00\x00\x00\x0a\x01", &RefCell::default()),
       Ok((
         &b""[..],
         Instruction {
@@ -265,10 +340,28 @@ mod tests {
         }
       ))
     );
+
+    let josa_table = RefCell::new(JosaTable::new());
+    assert_eq!(
+      instruction(
+        b"\x00\x00\x00\x02\x06\x02\x01\xec\x9d\x84\x01\x5f",
+        &josa_table
+      ),
+      Ok((
+        &b""[..],
+        Instruction {
+          line_number: 2,
+          opcode: Opcode::Call(vec![JosaId(1), BLANK])
+        }
+      ))
+    );
+    assert_eq!(josa_table.borrow().get("을"), Some(JosaId(1)));
   }
 
   #[test]
   fn parse_constant() {
+    let constant = |input| constant(input, &RefCell::default());
+
     assert_eq!(constant(b"\x00"), Ok((&b""[..], Constant::None)));
     assert_eq!(
       constant(b"\x01\x00\x00\x00\x00\x00\x00\x00\x7b"),
@@ -286,6 +379,30 @@ mod tests {
       constant(b"\x04\x01"),
       Ok((&b""[..], Constant::Boolean(true)))
     );
+    assert_eq!(
+      constant(b"\x07\x00\x00\x00\x00\x00\x00\x00\x02\xea\xb0\x80\xeb\x82\x98"),
+      Ok((&b""[..], Constant::Str("가나".into())))
+    );
+
+    assert_eq!(
+      constant(b"\x06\x01\x01\xec\x9d\x84\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x01\x02\x00\x00\x00\x01"),
+      Ok((
+        &b""[..],
+        Constant::Function {
+          josa_map: Rc::new(JosaMap::new(vec![JosaId(1)])),
+          rest_args: Some(Rc::default()),
+          func_object: FuncObject::CodeObject {
+            code: Rc::new(vec![Instruction {
+              line_number: 1,
+              opcode: Opcode::Load(1)
+            }]),
+            const_table: Rc::default(),
+            free_vars: Rc::default(),
+            caches: Rc::default(),
+          }
+        }
+      ))
+    );
 
     // let code_object = FuncObject::CodeObject {
     //   code: vec![
@@ -0,0 +1,158 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::HaneulError;
+
+/// 내장 함수가 파일에 접근할 수 있는 디렉터리입니다. 기본값은 아무 곳에도 접근할 수 없습니다.
+/// 허락한 디렉터리와 그 아래의 경로만 접근할 수 있으며, 경로의 `..`와 심볼릭 링크를 풀어서 판단합니다.
+#[derive(Debug, Default, Clone)]
+pub struct Permissions {
+  read: Vec<PathBuf>,
+  write: Vec<PathBuf>,
+}
+
+/// 심볼릭 링크를 따라가는 최대 횟수입니다. 서로를 가리키는 링크에서 멈추지 않는 것을 막습니다.
+const MAX_LINKS: usize = 40;
+
+/// 경로를 절대 경로로 바꾸고, 앞에서부터 차례로 심볼릭 링크를 풀어 실제 경로로 바꿉니다.
+/// 가리키는 파일이 아직 없는 심볼릭 링크도 링크의 대상으로 바꾸며, 존재하지 않는 부분은 글자 그대로 이어 붙입니다.
+fn resolve(path: &Path) -> io::Result<PathBuf> {
+  let absolute = if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    env::current_dir()?.join(path)
+  };
+  resolve_links(&absolute, &mut 0)
+}
+
+fn resolve_links(absolute: &Path, links: &mut usize) -> io::Result<PathBuf> {
+  let mut resolved = PathBuf::new();
+  for component in absolute.components() {
+    match component {
+      Component::Prefix(_) | Component::RootDir => resolved.push(component.as_os_str()),
+      Component::CurDir => {}
+      Component::ParentDir => {
+        resolved.pop();
+      }
+      Component::Normal(name) => {
+        resolved.push(name);
+        let is_link = fs::symlink_metadata(&resolved).is_ok_and(|x| x.file_type().is_symlink());
+        if is_link {
+          *links += 1;
+          if *links > MAX_LINKS {
+            return Err(io::Error::other("심볼릭 링크를 너무 많이 따라갔습니다"));
+          }
+          let target = fs::read_link(&resolved)?;
+          resolved.pop();
+          resolved = resolve_links(&resolved.join(target), links)?;
+        }
+      }
+    }
+  }
+
+  Ok(resolved)
+}
+
+fn is_allowed(allowed: &[PathBuf], path: &Path) -> bool {
+  match resolve(path) {
+    Ok(path) => allowed.iter().any(|dir| path.starts_with(dir)),
+    Err(_) => false,
+  }
+}
+
+impl Permissions {
+  pub fn new() -> Permissions {
+    Default::default()
+  }
+
+  /// `dir`과 그 아래의 경로를 읽을 수 있게 합니다.
+  pub fn allow_read<P: AsRef<Path>>(&mut self, dir: P) -> io::Result<()> {
+    self.read.push(resolve(dir.as_ref())?);
+    Ok(())
+  }
+
+  /// `dir`과 그 아래의 경로에 쓸 수 있게 합니다. 읽기 권한은 따로 주어야 합니다.
+  pub fn allow_write<P: AsRef<Path>>(&mut self, dir: P) -> io::Result<()> {
+    self.write.push(resolve(dir.as_ref())?);
+    Ok(())
+  }
+
+  pub fn check_read(&self, path: &str) -> Result<(), HaneulError> {
+    if is_allowed(&self.read, Path::new(path)) {
+      Ok(())
+    } else {
+      Err(HaneulError::PermissionDenied {
+        access: "읽기",
+        path: path.to_string(),
+      })
+    }
+  }
+
+  pub fn check_write(&self, path: &str) -> Result<(), HaneulError> {
+    if is_allowed(&self.write, Path::new(path)) {
+      Ok(())
+    } else {
+      Err(HaneulError::PermissionDenied {
+        access: "쓰기",
+        path: path.to_string(),
+      })
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn allow_only_granted_directories() {
+    let root = env::temp_dir().join(format!("haneul-permission-{}", std::process::id()));
+    let allowed = root.join("allowed");
+    let secret = root.join("secret");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&allowed).unwrap();
+    fs::create_dir_all(&secret).unwrap();
+
+    let mut permissions = Permissions::new();
+    let path = |path: &Path| path.to_str().unwrap().to_string();
+    assert!(permissions.check_read(&path(&allowed)).is_err());
+
+    permissions.allow_read(&allowed).unwrap();
+    assert!(permissions
+      .check_read(&path(&allowed.join("a.txt")))
+      .is_ok());
+    assert!(permissions
+      .check_read(&path(&allowed.join("new/../b.txt")))
+      .is_ok());
+    assert!(permissions
+      .check_read(&path(&allowed.join("../secret/a.txt")))
+      .is_err());
+    assert!(permissions.check_read(&path(&root)).is_err());
+    assert!(permissions
+      .check_write(&path(&allowed.join("a.txt")))
+      .is_err());
+
+    #[cfg(unix)]
+    {
+      let link = allowed.join("link");
+      std::os::unix::fs::symlink(&secret, &link).unwrap();
+      assert!(permissions.check_read(&path(&link.join("a.txt"))).is_err());
+
+      // 아직 없는 파일을 가리키는 링크도 대상 경로로 판단합니다.
+      permissions.allow_write(&allowed).unwrap();
+      let dangling = allowed.join("dangling");
+      std::os::unix::fs::symlink(secret.join("new.txt"), &dangling).unwrap();
+      assert!(permissions.check_write(&path(&dangling)).is_err());
+      let inside = allowed.join("inside");
+      std::os::unix::fs::symlink("new.txt", &inside).unwrap();
+      assert!(permissions.check_write(&path(&inside)).is_ok());
+      let looping = allowed.join("looping");
+      std::os::unix::fs::symlink(&looping, &looping).unwrap();
+      assert!(permissions.check_write(&path(&looping)).is_err());
+    }
+
+    fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default, Clone)]
+pub struct FunctionStats {
+  pub name: String,
+  pub calls: u64,
+  pub instructions: u64,
+  pub total_time: Duration,
+  pub self_time: Duration,
+}
+
+struct ActiveCall {
+  function: usize,
+  path: String,
+  started: Instant,
+  child_time: Duration,
+}
+
+/// 명령어 종류별, 줄별, 함수별 실행 횟수와 함수별 실행 시간을 모읍니다.
+#[derive(Default)]
+pub struct Profiler {
+  opcode_counts: HashMap<&'static str, u64>,
+  line_counts: HashMap<u32, u64>,
+  functions: Vec<FunctionStats>,
+  function_indices: HashMap<String, usize>,
+  active_calls: Vec<ActiveCall>,
+  collapsed_stacks: HashMap<String, Duration>,
+}
+
+impl Profiler {
+  pub fn new() -> Profiler {
+    Default::default()
+  }
+
+  pub fn record_instruction(&mut self, opcode_name: &'static str, line_number: u32) {
+    *self.opcode_counts.entry(opcode_name).or_insert(0) += 1;
+    *self.line_counts.entry(line_number).or_insert(0) += 1;
+
+    if let Some(call) = self.active_calls.last() {
+      self.functions[call.function].instructions += 1;
+    }
+  }
+
+  pub fn enter(&mut self, name: String) {
+    let function = match self.function_indices.get(&name) {
+      Some(index) => *index,
+      None => {
+        self.functions.push(FunctionStats {
+          name: name.clone(),
+          ..Default::default()
+        });
+        self
+          .function_indices
+          .insert(name.clone(), self.functions.len() - 1);
+        self.functions.len() - 1
+      }
+    };
+    self.functions[function].calls += 1;
+
+    let path = match self.active_calls.last() {
+      Some(parent) => format!("{};{}", parent.path, name),
+      None => name,
+    };
+
+    self.active_calls.push(ActiveCall {
+      function,
+      path,
+      started: Instant::now(),
+      child_time: Duration::default(),
+    });
+  }
+
+  pub fn exit(&mut self) {
+    let call = match self.active_calls.pop() {
+      Some(call) => call,
+      None => return,
+    };
+
+    let elapsed = call.started.elapsed();
+    let self_time = elapsed.saturating_sub(call.child_time);
+
+    let stats = &mut self.functions[call.function];
+    // 재귀 호출에서 같은 시간을 두 번 세지 않도록, 바깥쪽 호출의 전체 시간만 더합니다.
+    if !self
+      .active_calls
+      .iter()
+      .any(|x| x.function == call.function)
+    {
+      stats.total_time += elapsed;
+    }
+    stats.self_time += self_time;
+
+    *self.collapsed_stacks.entry(call.path).or_default() += self_time;
+
+    if let Some(parent) = self.active_calls.last_mut() {
+      parent.child_time += elapsed;
+    }
+  }
+
+  /// 아직 끝나지 않은 호출을 모두 끝난 것으로 칩니다.
+  pub fn exit_all(&mut self) {
+    while !self.active_calls.is_empty() {
+      self.exit();
+    }
+  }
+
+  pub fn opcode_counts(&self) -> &HashMap<&'static str, u64> {
+    &self.opcode_counts
+  }
+
+  pub fn line_counts(&self) -> &HashMap<u32, u64> {
+    &self.line_counts
+  }
+
+  pub fn functions(&self) -> &[FunctionStats] {
+    &self.functions
+  }
+
+  pub fn write_report(&self, out: &mut dyn Write) -> io::Result<()> {
+    let total: u64 = self.opcode_counts.values().sum();
+    let percent = |count: u64| {
+      if total == 0 {
+        0.0
+      } else {
+        count as f64 * 100.0 / total as f64
+      }
+    };
+
+    writeln!(out, "== 명령어별 실행 횟수 (전체 {}개) ==", total)?;
+    let mut opcodes: Vec<_> = self.opcode_counts.iter().collect();
+    opcodes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    for (name, count) in opcodes {
+      writeln!(
+        out,
+        "  {:<16} {:>12} {:>6.1}%",
+        name,
+        count,
+        percent(*count)
+      )?;
+    }
+
+    writeln!(out, "== 줄별 실행 횟수 ==")?;
+    let mut lines: Vec<_> = self.line_counts.iter().collect();
+    lines.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    for (line, count) in lines {
+      writeln!(
+        out,
+        "  {:>6}번째 줄 {:>12} {:>6.1}%",
+        line,
+        count,
+        percent(*count)
+      )?;
+    }
+
+    writeln!(out, "== 함수별 통계 ==")?;
+    writeln!(
+      out,
+      "  {:>10} {:>12} {:>12} {:>12}  이름",
+      "호출", "명령어", "전체(ms)", "자체(ms)"
+    )?;
+    let mut functions: Vec<_> = self.functions.iter().collect();
+    functions.sort_by(|a, b| b.total_time.cmp(&a.total_time).then(a.name.cmp(&b.name)));
+    for stats in functions {
+      writeln!(
+        out,
+        "  {:>10} {:>12} {:>12.3} {:>12.3}  {}",
+        stats.calls,
+        stats.instructions,
+        stats.total_time.as_secs_f64() * 1000.0,
+        stats.self_time.as_secs_f64() * 1000.0,
+        stats.name
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// flamegraph.pl 등이 읽을 수 있는 접힌 스택(collapsed stack) 형식으로 씁니다.
+  /// 각 줄의 값은 그 호출 경로에서 보낸 자체 시간(나노초)입니다.
+  pub fn write_collapsed(&self, out: &mut dyn Write) -> io::Result<()> {
+    let mut stacks: Vec<_> = self.collapsed_stacks.iter().collect();
+    stacks.sort();
+    for (path, time) in stacks {
+      writeln!(out, "{} {}", path, time.as_nanos())?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use crate::constant::Constant;
+  use crate::funcobject::FuncObject;
+  use crate::instruction::Instruction;
+  use crate::josa::{JosaMap, JosaTable};
+  use crate::machine::{Machine, StackFrame};
+  use crate::opcode::Opcode;
+
+  fn inst(line_number: u32, opcode: Opcode) -> Instruction {
+    Instruction {
+      line_number,
+      opcode,
+    }
+  }
+
+  #[test]
+  fn profile_code_and_native_calls() {
+    let mut josa_table = JosaTable::new();
+    let eul = josa_table.intern("을");
+
+    let identity = Constant::Function {
+      josa_map: Rc::new(JosaMap::new(vec![eul])),
+      rest_args: None,
+      func_object: FuncObject::NativeFunc {
+        name: "항등",
+        function: |_, args| Ok(args[0].clone()),
+      },
+    };
+    let wrapper = Constant::Function {
+      josa_map: Rc::new(JosaMap::new(vec![eul])),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(vec![
+          inst(2, Opcode::Load(0)),
+          inst(2, Opcode::LoadGlobal(0)),
+          inst(2, Opcode::Call(vec![eul])),
+        ]),
+        const_table: Rc::default(),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    };
+
+    let code = vec![
+      inst(1, Opcode::Push(0)),
+      inst(1, Opcode::Push(1)),
+      inst(1, Opcode::Call(vec![eul])),
+      inst(1, Opcode::Pop),
+    ];
+
+    let mut machine = Machine::new(vec![Some(identity)], vec![String::from("항등")], josa_table);
+    machine.set_profiler(Some(Profiler::new()));
+    assert!(machine
+      .run(StackFrame::new(code, vec![Constant::Integer(3), wrapper]))
+      .is_ok());
+
+    let profiler = machine.take_profiler().unwrap();
+    assert_eq!(profiler.opcode_counts()["Call"], 2);
+    assert_eq!(profiler.line_counts()[&1], 4);
+    assert_eq!(profiler.line_counts()[&2], 3);
+
+    let names: Vec<(&str, u64, u64)> = profiler
+      .functions()
+      .iter()
+      .map(|x| (x.name.as_str(), x.calls, x.instructions))
+      .collect();
+    assert_eq!(
+      names,
+      vec![
+        ("(최상위)", 1, 4),
+        ("함수(을) 2번째 줄", 1, 3),
+        ("항등", 1, 0)
+      ]
+    );
+
+    let mut collapsed = Vec::new();
+    profiler.write_collapsed(&mut collapsed).unwrap();
+    let paths: Vec<String> = String::from_utf8(collapsed)
+      .unwrap()
+      .lines()
+      .map(|line| line.rsplit_once(' ').unwrap().0.to_string())
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "(최상위)",
+        "(최상위);함수(을) 2번째 줄",
+        "(최상위);함수(을) 2번째 줄;항등"
+      ]
+    );
+  }
+}
@@ -1,9 +1,51 @@
+use std::collections::BTreeSet;
+
 use crate::constant::Constant;
+use crate::funcobject::FuncObject;
 use crate::instruction::Instruction;
+use crate::josa::JosaTable;
+
+/// 다른 모듈에서 가져오는 전역 변수들입니다.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Import {
+  pub module: String,
+  pub names: Vec<String>,
+}
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Program {
   pub const_table: Vec<Constant>,
   pub code: Vec<Instruction>,
   pub global_var_names: Vec<String>,
+  /// 프로그램에 나오는 모든 조사의 번호입니다. 내장 함수의 조사도 같은 테이블에 등록해야 합니다.
+  pub josa_table: JosaTable,
+  /// 실행하기 전에 불러와야 하는 모듈들입니다.
+  pub imports: Vec<Import>,
+  /// 다른 모듈이 가져갈 수 있는 전역 변수의 이름입니다.
+  pub exports: Vec<String>,
+}
+
+impl Program {
+  /// 최상위 코드와 상수 테이블 안의 모든 함수에 있는 명령어의 라인 번호입니다.
+  pub fn line_numbers(&self) -> BTreeSet<u32> {
+    let mut lines = BTreeSet::new();
+    collect_line_numbers(&self.code, &self.const_table, &mut lines);
+    lines
+  }
+}
+
+fn collect_line_numbers(code: &[Instruction], const_table: &[Constant], lines: &mut BTreeSet<u32>) {
+  lines.extend(code.iter().map(|inst| inst.line_number));
+
+  for value in const_table {
+    if let Constant::Function {
+      func_object: FuncObject::CodeObject {
+        code, const_table, ..
+      },
+      ..
+    } = value
+    {
+      collect_line_numbers(code, const_table, lines);
+    }
+  }
 }
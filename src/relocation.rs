@@ -0,0 +1,116 @@
+use std::rc::Rc;
+
+use crate::constant::Constant;
+use crate::funcobject::FuncObject;
+use crate::instruction::Instruction;
+use crate::josa::{JosaId, JosaMap};
+use crate::opcode::Opcode;
+use crate::program::Program;
+
+/// 한 프로그램의 전역 변수와 조사 번호를 다른 테이블의 번호로 바꾸는 표입니다.
+pub(crate) struct Relocation {
+  pub globals: Vec<u32>,
+  pub josa: Vec<JosaId>,
+}
+
+impl Relocation {
+  /// 번호가 모두 그대로인 표입니다.
+  pub fn is_identity(&self) -> bool {
+    self
+      .globals
+      .iter()
+      .enumerate()
+      .all(|(index, global)| *global as usize == index)
+      && self
+        .josa
+        .iter()
+        .enumerate()
+        .all(|(index, josa)| josa.0 as usize == index)
+  }
+
+  fn josa(&self, josa: JosaId) -> JosaId {
+    self.josa[josa.0 as usize]
+  }
+
+  /// `constants`는 원래 상수 번호에 대한 새 상수 번호이고, `jump_offset`은 코드를 이어 붙인 위치입니다.
+  pub fn instruction(
+    &self,
+    inst: &Instruction,
+    constants: &[u32],
+    jump_offset: u32,
+  ) -> Instruction {
+    let opcode = match &inst.opcode {
+      Opcode::Push(index) => Opcode::Push(constants[*index as usize]),
+      Opcode::LoadGlobal(index) => Opcode::LoadGlobal(self.globals[*index as usize]),
+      Opcode::StoreGlobal(index) => Opcode::StoreGlobal(self.globals[*index as usize]),
+      Opcode::Call(josa_list) => Opcode::Call(josa_list.iter().map(|x| self.josa(*x)).collect()),
+      Opcode::Jmp(target) => Opcode::Jmp(target + jump_offset),
+      Opcode::PopJmpIfFalse(target) => Opcode::PopJmpIfFalse(target + jump_offset),
+      opcode => opcode.clone(),
+    };
+
+    Instruction {
+      line_number: inst.line_number,
+      opcode,
+    }
+  }
+
+  /// 함수 상수 안의 코드와 상수 테이블도 바꿉니다. 함수의 상수 번호는 그대로 둡니다.
+  pub fn constant(&self, value: &Constant) -> Constant {
+    match value {
+      Constant::Function {
+        josa_map,
+        rest_args,
+        func_object:
+          FuncObject::CodeObject {
+            code,
+            const_table,
+            free_vars,
+            ..
+          },
+      } => {
+        let mut new_josa_map = JosaMap::new(josa_map.keys().map(|x| self.josa(x)));
+        for (index, value) in josa_map.values().enumerate() {
+          *new_josa_map.value_mut(index) = value.as_ref().map(|x| self.constant(x));
+        }
+
+        let constants: Vec<u32> = (0..const_table.len() as u32).collect();
+        Constant::Function {
+          josa_map: Rc::new(new_josa_map),
+          rest_args: rest_args
+            .as_ref()
+            .map(|args| Rc::new(args.iter().map(|x| self.constant(x)).collect())),
+          func_object: FuncObject::CodeObject {
+            code: Rc::new(
+              code
+                .iter()
+                .map(|inst| self.instruction(inst, &constants, 0))
+                .collect(),
+            ),
+            const_table: Rc::new(const_table.iter().map(|x| self.constant(x)).collect()),
+            free_vars: Rc::new(free_vars.iter().map(|x| self.constant(x)).collect()),
+            caches: Rc::default(),
+          },
+        }
+      }
+      Constant::List(values) => {
+        Constant::List(Rc::new(values.iter().map(|x| self.constant(x)).collect()))
+      }
+      value => value.clone(),
+    }
+  }
+
+  /// 프로그램의 최상위 코드와 상수 테이블을 그 자리에서 바꿉니다. 번호가 모두 그대로면 아무것도 하지 않습니다.
+  pub fn program(&self, program: &mut Program) {
+    if self.is_identity() {
+      return;
+    }
+    let constants: Vec<u32> = (0..program.const_table.len() as u32).collect();
+    for value in &mut program.const_table {
+      *value = self.constant(value);
+    }
+    for inst in &mut program.code {
+      *inst = self.instruction(inst, &constants, 0);
+    }
+  }
+}
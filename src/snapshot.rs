@@ -0,0 +1,563 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::error;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::constant::Constant;
+use crate::funcobject::{FuncObject, NativeFunction};
+use crate::inline_cache::InlineCaches;
+use crate::instruction::Instruction;
+use crate::josa::{JosaId, JosaMap, JosaTable};
+use crate::machine::StackFrame;
+use crate::opcode::{BinaryOp, Opcode, UnaryOp};
+
+// 명령어 번호는 .hnc 파일과 같지만, 조사는 문자열 대신 조사 테이블의 번호로 씁니다.
+const MAGIC: &[u8; 4] = b"HNSS";
+const VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+  InvalidFormat { reason: String },
+  UnsupportedVersion { version: u8 },
+  UnknownNative { name: String },
+}
+
+impl fmt::Display for SnapshotError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SnapshotError::InvalidFormat { reason } => {
+        write!(f, "올바른 스냅샷 파일이 아닙니다 : {}", reason)
+      }
+      SnapshotError::UnsupportedVersion { version } => {
+        write!(f, "지원하지 않는 스냅샷 버전입니다 : {}", version)
+      }
+      SnapshotError::UnknownNative { name } => {
+        write!(f, "스냅샷에 있는 내장 함수 '{}'를 찾을 수 없습니다.", name)
+      }
+    }
+  }
+}
+
+impl error::Error for SnapshotError {}
+
+fn invalid(reason: &str) -> SnapshotError {
+  SnapshotError::InvalidFormat {
+    reason: reason.to_string(),
+  }
+}
+
+/// 멈춰 있는 가상 머신의 상태입니다. 바이트로 바꿔 저장했다가 다른 곳에서 이어서 실행할 수 있습니다.
+/// 실행 제한, 추적기, 프로파일러, 커버리지는 상태에 포함되지 않습니다.
+pub struct Snapshot {
+  pub(crate) operand_stack: Vec<Constant>,
+  pub(crate) call_stack: Vec<StackFrame>,
+  pub(crate) global_vars: Vec<Option<Constant>>,
+  pub(crate) global_var_names: Vec<String>,
+  pub(crate) josa_table: JosaTable,
+  pub(crate) executed_count: u64,
+  pub(crate) fuel: Option<u64>,
+}
+
+impl Snapshot {
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.out.extend_from_slice(MAGIC);
+    writer.u8(VERSION);
+
+    writer.u32(self.josa_table.names().len() as u32);
+    for name in self.josa_table.names() {
+      writer.string(name);
+    }
+
+    writer.u32(self.global_var_names.len() as u32);
+    for name in &self.global_var_names {
+      writer.string(name);
+    }
+
+    writer.u32(self.global_vars.len() as u32);
+    for value in &self.global_vars {
+      writer.optional_value(value.as_ref());
+    }
+
+    writer.values(&self.operand_stack);
+
+    writer.u32(self.call_stack.len() as u32);
+    for frame in &self.call_stack {
+      writer.code_object(&frame.code, &frame.const_table);
+      writer.values(&frame.free_vars);
+      writer.u64(frame.slot_start as u64);
+      writer.u64(frame.ip as u64);
+      writer.josa_list(&frame.josa_list);
+      writer.u8(frame.variadic as u8);
+    }
+
+    writer.u64(self.executed_count);
+    match self.fuel {
+      Some(fuel) => {
+        writer.u8(1);
+        writer.u64(fuel);
+      }
+      None => writer.u8(0),
+    }
+
+    writer.out
+  }
+
+  /// 스냅샷을 읽습니다. 내장 함수는 이름으로 저장되므로, `natives`에서 같은 이름의 내장 함수를 찾아 되살립니다.
+  /// 보통은 `builtin_functions`가 돌려주는 내장 함수 목록을 넘기면 됩니다.
+  pub fn from_bytes(input: &[u8], natives: &[Constant]) -> Result<Snapshot, SnapshotError> {
+    let mut reader = Reader {
+      input,
+      natives: natives
+        .iter()
+        .filter_map(|value| match value {
+          Constant::Function {
+            func_object: FuncObject::NativeFunc { name, function },
+            ..
+          } => Some((*name, (*name, *function))),
+          _ => None,
+        })
+        .collect(),
+      code_objects: Vec::new(),
+      josa_count: 0,
+    };
+
+    if reader.bytes(MAGIC.len())? != MAGIC {
+      return Err(invalid("파일 머리가 다릅니다"));
+    }
+    let version = reader.u8()?;
+    if version != VERSION {
+      return Err(SnapshotError::UnsupportedVersion { version });
+    }
+
+    let mut josa_table = JosaTable::new();
+    for index in 0..reader.u32()? {
+      let name = reader.string()?;
+      if josa_table.intern(&name) != JosaId(index) {
+        return Err(invalid("조사 테이블이 잘못되었습니다"));
+      }
+    }
+    reader.josa_count = josa_table.names().len() as u32;
+
+    let global_var_names = (0..reader.u32()?)
+      .map(|_| reader.string())
+      .collect::<Result<Vec<_>, _>>()?;
+    let global_vars = (0..reader.u32()?)
+      .map(|_| reader.optional_value())
+      .collect::<Result<Vec<_>, _>>()?;
+    if global_vars.len() != global_var_names.len() {
+      return Err(invalid("전역 변수의 개수가 맞지 않습니다"));
+    }
+
+    let operand_stack = reader.values()?;
+
+    let mut call_stack = Vec::new();
+    let frame_count = reader.u32()?;
+    for index in 0..frame_count {
+      let (code, const_table, caches) = reader.code_object()?;
+      let free_vars = Rc::new(reader.values()?);
+      let slot_start = reader.u64()? as usize;
+      let ip = reader.u64()? as usize;
+      let josa_list = reader.josa_list()?;
+      let variadic = reader.u8()? != 0;
+
+      // 호출한 프레임은 마지막 명령어인 호출 명령어 다음에서 멈출 수 있지만, 최상위 프레임은 실행할 명령어를 가리켜야 합니다.
+      let is_top = index + 1 == frame_count;
+      if ip > code.len() || (is_top && ip == code.len()) || slot_start > operand_stack.len() {
+        return Err(invalid("실행 위치가 잘못되었습니다"));
+      }
+
+      call_stack.push(StackFrame {
+        code,
+        const_table,
+        free_vars,
+        caches,
+        slot_start,
+        ip,
+        josa_list,
+        variadic,
+      });
+    }
+
+    let executed_count = reader.u64()?;
+    let fuel = match reader.u8()? {
+      0 => None,
+      _ => Some(reader.u64()?),
+    };
+
+    if !reader.input.is_empty() {
+      return Err(invalid("파일 끝에 알 수 없는 내용이 있습니다"));
+    }
+
+    Ok(Snapshot {
+      operand_stack,
+      call_stack,
+      global_vars,
+      global_var_names,
+      josa_table,
+      executed_count,
+      fuel,
+    })
+  }
+}
+
+/// 같은 코드 객체를 공유하는 값들은 코드 객체를 한 번만 쓰고, 그 뒤로는 번호로 가리킵니다.
+#[derive(Default)]
+struct Writer {
+  out: Vec<u8>,
+  code_objects: HashMap<*const Vec<Instruction>, u32>,
+}
+
+impl Writer {
+  fn u8(&mut self, value: u8) {
+    self.out.push(value);
+  }
+
+  fn u32(&mut self, value: u32) {
+    self.out.extend_from_slice(&value.to_be_bytes());
+  }
+
+  fn u64(&mut self, value: u64) {
+    self.out.extend_from_slice(&value.to_be_bytes());
+  }
+
+  fn string(&mut self, value: &str) {
+    self.u32(value.len() as u32);
+    self.out.extend_from_slice(value.as_bytes());
+  }
+
+  fn josa_list(&mut self, josa_list: &[JosaId]) {
+    self.u32(josa_list.len() as u32);
+    for josa in josa_list {
+      self.u32(josa.0);
+    }
+  }
+
+  fn values(&mut self, values: &[Constant]) {
+    self.u32(values.len() as u32);
+    for value in values {
+      self.value(value);
+    }
+  }
+
+  fn optional_value(&mut self, value: Option<&Constant>) {
+    match value {
+      Some(value) => {
+        self.u8(1);
+        self.value(value);
+      }
+      None => self.u8(0),
+    }
+  }
+
+  fn value(&mut self, value: &Constant) {
+    match value {
+      Constant::None => self.u8(0),
+      Constant::Integer(value) => {
+        self.u8(1);
+        self.u64(*value as u64);
+      }
+      Constant::Real(value) => {
+        self.u8(2);
+        self.u64(value.to_bits());
+      }
+      Constant::Char(value) => {
+        self.u8(3);
+        self.u32(*value as u32);
+      }
+      Constant::Boolean(value) => {
+        self.u8(4);
+        self.u8(*value as u8);
+      }
+      Constant::Str(value) => {
+        self.u8(7);
+        self.string(value);
+      }
+      Constant::List(values) => {
+        self.u8(5);
+        self.values(values);
+      }
+      Constant::Function {
+        josa_map,
+        rest_args,
+        func_object,
+      } => {
+        self.u8(6);
+        self.u32(josa_map.len() as u32);
+        for (josa, value) in josa_map.iter() {
+          self.u32(josa.0);
+          self.optional_value(value.as_ref());
+        }
+
+        match rest_args {
+          Some(rest) => {
+            self.u8(1);
+            self.values(rest);
+          }
+          None => self.u8(0),
+        }
+
+        match func_object {
+          FuncObject::CodeObject {
+            code,
+            const_table,
+            free_vars,
+            ..
+          } => {
+            self.u8(0);
+            self.code_object(code, const_table);
+            self.values(free_vars);
+          }
+          FuncObject::NativeFunc { name, .. } => {
+            self.u8(1);
+            self.string(name);
+          }
+        }
+      }
+    }
+  }
+
+  fn code_object(&mut self, code: &Rc<Vec<Instruction>>, const_table: &[Constant]) {
+    if let Some(index) = self.code_objects.get(&Rc::as_ptr(code)).copied() {
+      self.u8(0);
+      self.u32(index);
+      return;
+    }
+
+    self.u8(1);
+    self.values(const_table);
+    self.u32(code.len() as u32);
+    for inst in code.iter() {
+      self.instruction(inst);
+    }
+
+    // 읽는 쪽과 번호가 같도록 상수 테이블 안의 코드 객체들을 다 쓴 뒤에 번호를 붙입니다.
+    let index = self.code_objects.len() as u32;
+    self.code_objects.insert(Rc::as_ptr(code), index);
+  }
+
+  fn instruction(&mut self, inst: &Instruction) {
+    self.u32(inst.line_number);
+    match &inst.opcode {
+      Opcode::Push(v) => self.operand(0, *v),
+      Opcode::Pop => self.u8(1),
+      Opcode::Load(v) => self.operand(2, *v),
+      Opcode::LoadDeref(v) => self.operand(3, *v),
+      Opcode::StoreGlobal(v) => self.operand(4, *v),
+      Opcode::LoadGlobal(v) => self.operand(5, *v),
+      Opcode::Call(josa_list) => {
+        self.u8(6);
+        self.josa_list(josa_list);
+      }
+      Opcode::Jmp(v) => self.operand(7, *v),
+      Opcode::PopJmpIfFalse(v) => self.operand(8, *v),
+      Opcode::FreeVarLocal(v) => {
+        self.u8(9);
+        self.u8(*v);
+      }
+      Opcode::FreeVarFree(v) => {
+        self.u8(10);
+        self.u8(*v);
+      }
+      Opcode::BinaryOp(BinaryOp::Add) => self.u8(11),
+      Opcode::BinaryOp(BinaryOp::Subtract) => self.u8(12),
+      Opcode::BinaryOp(BinaryOp::Multiply) => self.u8(13),
+      Opcode::BinaryOp(BinaryOp::Divide) => self.u8(14),
+      Opcode::BinaryOp(BinaryOp::Mod) => self.u8(15),
+      Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Equal)) => self.u8(16),
+      Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Less)) => self.u8(17),
+      Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Greater)) => self.u8(18),
+      Opcode::UnaryOp(UnaryOp::Negate) => self.u8(19),
+    }
+  }
+
+  fn operand(&mut self, opcode: u8, value: u32) {
+    self.u8(opcode);
+    self.u32(value);
+  }
+}
+
+type CodeObjectParts = (Rc<Vec<Instruction>>, Rc<Vec<Constant>>, Rc<InlineCaches>);
+
+struct Reader<'a> {
+  input: &'a [u8],
+  natives: HashMap<&'static str, (&'static str, NativeFunction)>,
+  code_objects: Vec<CodeObjectParts>,
+  josa_count: u32,
+}
+
+impl<'a> Reader<'a> {
+  fn bytes(&mut self, count: usize) -> Result<&'a [u8], SnapshotError> {
+    if self.input.len() < count {
+      return Err(invalid("파일이 중간에 끝났습니다"));
+    }
+
+    let (result, rest) = self.input.split_at(count);
+    self.input = rest;
+    Ok(result)
+  }
+
+  fn u8(&mut self) -> Result<u8, SnapshotError> {
+    Ok(self.bytes(1)?[0])
+  }
+
+  fn u32(&mut self) -> Result<u32, SnapshotError> {
+    Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+  }
+
+  fn u64(&mut self) -> Result<u64, SnapshotError> {
+    Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+  }
+
+  fn string(&mut self) -> Result<String, SnapshotError> {
+    let length = self.u32()? as usize;
+    String::from_utf8(self.bytes(length)?.to_vec())
+      .map_err(|_| invalid("문자열이 UTF-8이 아닙니다"))
+  }
+
+  fn josa(&mut self) -> Result<JosaId, SnapshotError> {
+    let id = self.u32()?;
+    if id >= self.josa_count {
+      return Err(invalid("조사 번호가 조사 테이블을 벗어났습니다"));
+    }
+    Ok(JosaId(id))
+  }
+
+  fn josa_list(&mut self) -> Result<Vec<JosaId>, SnapshotError> {
+    (0..self.u32()?).map(|_| self.josa()).collect()
+  }
+
+  fn values(&mut self) -> Result<Vec<Constant>, SnapshotError> {
+    (0..self.u32()?).map(|_| self.value()).collect()
+  }
+
+  fn optional_value(&mut self) -> Result<Option<Constant>, SnapshotError> {
+    match self.u8()? {
+      0 => Ok(None),
+      _ => Ok(Some(self.value()?)),
+    }
+  }
+
+  fn value(&mut self) -> Result<Constant, SnapshotError> {
+    let value = match self.u8()? {
+      0 => Constant::None,
+      1 => Constant::Integer(self.u64()? as i64),
+      2 => Constant::Real(f64::from_bits(self.u64()?)),
+      3 => match std::char::from_u32(self.u32()?) {
+        Some(value) => Constant::Char(value),
+        None => return Err(invalid("올바른 문자가 아닙니다")),
+      },
+      4 => Constant::Boolean(self.u8()? != 0),
+      5 => Constant::List(Rc::new(self.values()?)),
+      6 => {
+        let mut slots = Vec::new();
+        for _ in 0..self.u32()? {
+          slots.push((self.josa()?, self.optional_value()?));
+        }
+        let mut josa_map = JosaMap::new(slots.iter().map(|(josa, _)| *josa));
+        for (index, (_, value)) in slots.into_iter().enumerate() {
+          *josa_map.value_mut(index) = value;
+        }
+
+        let rest_args = match self.u8()? {
+          0 => None,
+          _ => Some(Rc::new(self.values()?)),
+        };
+
+        let func_object = match self.u8()? {
+          0 => {
+            let (code, const_table, caches) = self.code_object()?;
+            FuncObject::CodeObject {
+              code,
+              const_table,
+              free_vars: Rc::new(self.values()?),
+              caches,
+            }
+          }
+          1 => {
+            let name = self.string()?;
+            match self.natives.get(name.as_str()) {
+              Some((name, function)) => FuncObject::NativeFunc {
+                name,
+                function: *function,
+              },
+              None => return Err(SnapshotError::UnknownNative { name }),
+            }
+          }
+          _ => return Err(invalid("알 수 없는 함수 종류입니다")),
+        };
+
+        Constant::Function {
+          josa_map: Rc::new(josa_map),
+          rest_args,
+          func_object,
+        }
+      }
+      7 => Constant::Str(self.string()?.into()),
+      _ => return Err(invalid("알 수 없는 값 종류입니다")),
+    };
+
+    Ok(value)
+  }
+
+  fn code_object(&mut self) -> Result<CodeObjectParts, SnapshotError> {
+    if self.u8()? == 0 {
+      let index = self.u32()? as usize;
+      return match self.code_objects.get(index) {
+        Some((code, const_table, caches)) => {
+          Ok((Rc::clone(code), Rc::clone(const_table), Rc::clone(caches)))
+        }
+        None => Err(invalid("코드 객체 번호가 잘못되었습니다")),
+      };
+    }
+
+    let const_table = Rc::new(self.values()?);
+    let code = Rc::new(
+      (0..self.u32()?)
+        .map(|_| self.instruction())
+        .collect::<Result<Vec<_>, _>>()?,
+    );
+    let caches = Rc::new(InlineCaches::default());
+
+    self.code_objects.push((
+      Rc::clone(&code),
+      Rc::clone(&const_table),
+      Rc::clone(&caches),
+    ));
+    Ok((code, const_table, caches))
+  }
+
+  fn instruction(&mut self) -> Result<Instruction, SnapshotError> {
+    let line_number = self.u32()?;
+    let opcode = match self.u8()? {
+      0 => Opcode::Push(self.u32()?),
+      1 => Opcode::Pop,
+      2 => Opcode::Load(self.u32()?),
+      3 => Opcode::LoadDeref(self.u32()?),
+      4 => Opcode::StoreGlobal(self.u32()?),
+      5 => Opcode::LoadGlobal(self.u32()?),
+      6 => Opcode::Call(self.josa_list()?),
+      7 => Opcode::Jmp(self.u32()?),
+      8 => Opcode::PopJmpIfFalse(self.u32()?),
+      9 => Opcode::FreeVarLocal(self.u8()?),
+      10 => Opcode::FreeVarFree(self.u8()?),
+      11 => Opcode::BinaryOp(BinaryOp::Add),
+      12 => Opcode::BinaryOp(BinaryOp::Subtract),
+      13 => Opcode::BinaryOp(BinaryOp::Multiply),
+      14 => Opcode::BinaryOp(BinaryOp::Divide),
+      15 => Opcode::BinaryOp(BinaryOp::Mod),
+      16 => Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Equal)),
+      17 => Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Less)),
+      18 => Opcode::BinaryOp(BinaryOp::Cmp(Ordering::Greater)),
+      19 => Opcode::UnaryOp(UnaryOp::Negate),
+      _ => return Err(invalid("알 수 없는 명령어입니다")),
+    };
+
+    Ok(Instruction {
+      line_number,
+      opcode,
+    })
+  }
+}
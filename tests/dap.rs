@@ -0,0 +1,151 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+fn encode(messages: &str) -> Vec<u8> {
+  let mut result = Vec::new();
+  for message in messages.lines().filter(|x| !x.trim().is_empty()) {
+    write!(
+      result,
+      "Content-Length: {}\r\n\r\n{}",
+      message.len(),
+      message
+    )
+    .unwrap();
+  }
+  result
+}
+
+fn decode(mut output: &str) -> Vec<Value> {
+  let mut result = Vec::new();
+  while let Some(header_end) = output.find("\r\n\r\n") {
+    let length: usize = output[..header_end]
+      .trim_start_matches("Content-Length:")
+      .trim()
+      .parse()
+      .unwrap();
+    let body_start = header_end + 4;
+    result.push(serde_json::from_str(&output[body_start..body_start + length]).unwrap());
+    output = &output[body_start + length..];
+  }
+  result
+}
+
+fn kind(message: &Value) -> String {
+  match message["type"].as_str().unwrap() {
+    "response" => format!("response {}", message["command"].as_str().unwrap()),
+    _ => format!("event {}", message["event"].as_str().unwrap()),
+  }
+}
+
+fn run_session(requests: &str) -> Vec<Value> {
+  let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+  let requests = requests.replace("${FIXTURES}", fixtures.to_str().unwrap());
+
+  let mut child = Command::new(env!("CARGO_BIN_EXE_haneul"))
+    .arg("dap")
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap();
+  child
+    .stdin
+    .take()
+    .unwrap()
+    .write_all(&encode(&requests))
+    .unwrap();
+
+  let output = child.wait_with_output().unwrap();
+  assert!(output.status.success());
+  decode(&String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn replay_debug_session() {
+  let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+  let messages = run_session(&fs::read_to_string(fixtures.join("dap_session.jsonl")).unwrap());
+
+  let kinds: Vec<String> = messages.iter().map(kind).collect();
+  assert_eq!(
+    kinds,
+    vec![
+      "response initialize",
+      "event initialized",
+      "response launch",
+      "response setBreakpoints",
+      "response configurationDone",
+      "event stopped",
+      "response stackTrace",
+      "response scopes",
+      "response variables",
+      "response stepOut",
+      "event stopped",
+      "response next",
+      "event stopped",
+      "response variables",
+      "response continue",
+      "event exited",
+      "event terminated",
+      "response disconnect",
+    ]
+  );
+  assert!(messages
+    .iter()
+    .filter(|x| x["type"] == "response")
+    .all(|x| x["success"] == true));
+
+  let breakpoints = &messages[3]["body"]["breakpoints"];
+  assert_eq!(breakpoints[0]["verified"], true);
+  assert_eq!(breakpoints[1]["verified"], false);
+
+  assert_eq!(messages[5]["body"]["reason"], "breakpoint");
+
+  let frames = messages[6]["body"]["stackFrames"].as_array().unwrap();
+  let lines: Vec<u64> = frames.iter().map(|x| x["line"].as_u64().unwrap()).collect();
+  assert_eq!(lines, vec![2, 4]);
+
+  let scopes = messages[7]["body"]["scopes"].as_array().unwrap();
+  assert_eq!(scopes[0]["variablesReference"], 4);
+
+  let locals = &messages[8]["body"]["variables"];
+  assert_eq!(locals[0]["name"], "을");
+  assert_eq!(locals[0]["value"], "Integer(21)");
+
+  assert_eq!(messages[10]["body"]["reason"], "step");
+  assert_eq!(messages[12]["body"]["reason"], "step");
+
+  let globals = messages[13]["body"]["variables"].as_array().unwrap();
+  let result = globals.iter().find(|x| x["name"] == "결과").unwrap();
+  assert_eq!(result["value"], "Integer(42)");
+
+  assert_eq!(messages[15]["body"]["exitCode"], 0);
+}
+
+#[test]
+fn send_program_output_as_events() {
+  let messages = run_session(
+    r#"
+{"seq":1,"type":"request","command":"initialize","arguments":{"adapterID":"haneul"}}
+{"seq":2,"type":"request","command":"launch","arguments":{"program":"${FIXTURES}/print.hnc"}}
+{"seq":3,"type":"request","command":"configurationDone"}
+{"seq":4,"type":"request","command":"disconnect"}
+"#,
+  );
+
+  let outputs: Vec<&Value> = messages
+    .iter()
+    .filter(|x| x["event"] == "output")
+    .map(|x| &x["body"])
+    .collect();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0]["category"], "stdout");
+  assert_eq!(outputs[0]["output"], "42\n42 가\n");
+
+  let kinds: Vec<String> = messages.iter().map(kind).collect();
+  let output_index = kinds.iter().position(|x| x == "event output").unwrap();
+  let exited_index = kinds.iter().position(|x| x == "event exited").unwrap();
+  assert!(output_index < exited_index);
+}
@@ -0,0 +1,262 @@
+use std::fs;
+use std::rc::Rc;
+
+use haneul::builtin::{builtin_functions, get_builtin};
+use haneul::constant::Constant;
+use haneul::encoder::{encode, EncodeError};
+use haneul::funcobject::FuncObject;
+use haneul::instruction::Instruction;
+use haneul::josa::{JosaMap, JosaTable};
+use haneul::linker::{link, LinkError, LinkUnit};
+use haneul::machine::{Machine, StackFrame};
+use haneul::opcode::{BinaryOp, Opcode};
+use haneul::parser::program;
+use haneul::program::{Import, Program};
+
+fn instructions(code: Vec<Opcode>) -> Vec<Instruction> {
+  code
+    .into_iter()
+    .map(|opcode| Instruction {
+      line_number: 1,
+      opcode,
+    })
+    .collect()
+}
+
+fn load(path: &str) -> Program {
+  let data = fs::read(path).unwrap();
+  program(&data[..]).unwrap().1
+}
+
+fn unit(name: &str, program: Program) -> LinkUnit {
+  LinkUnit {
+    name: name.to_string(),
+    program,
+  }
+}
+
+fn module(name: &str) -> LinkUnit {
+  unit(name, load(&format!("tests/fixtures/modules/{}.hnc", name)))
+}
+
+/// 링크한 프로그램을 실행하고 전역 변수 `결과`의 값을 돌려줍니다.
+fn run(mut program: Program) -> Option<Constant> {
+  let globals = get_builtin(&mut program.josa_table, &program.global_var_names);
+  let mut machine = Machine::new(
+    globals,
+    program.global_var_names.clone(),
+    program.josa_table.clone(),
+  );
+  assert!(machine
+    .run(StackFrame::new(program.code, program.const_table))
+    .is_ok());
+  machine.global("결과").unwrap().cloned()
+}
+
+#[test]
+fn encode_parsed_programs() {
+  let paths = fs::read_dir("tests/fixtures")
+    .unwrap()
+    .chain(fs::read_dir("tests/fixtures/modules").unwrap())
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().is_some_and(|x| x == "hnc"));
+
+  for path in paths {
+    let data = fs::read(&path).unwrap();
+    let (_, parsed) = program(&data[..]).unwrap();
+    assert_eq!(encode(&parsed).unwrap(), data, "{}", path.display());
+  }
+}
+
+#[test]
+fn report_unencodable_constants() {
+  let native = builtin_functions(&mut JosaTable::new()).remove(0);
+  for value in [Constant::List(Rc::new(vec![Constant::Integer(1)])), native] {
+    let program = Program {
+      const_table: vec![value],
+      code: instructions(vec![Opcode::Push(0)]),
+      global_var_names: Vec::new(),
+      josa_table: JosaTable::new(),
+      imports: Vec::new(),
+      exports: Vec::new(),
+    };
+    assert!(matches!(
+      encode(&program),
+      Err(EncodeError::UnsupportedConstant { .. })
+    ));
+  }
+}
+
+#[test]
+fn link_imported_modules() {
+  let mut josa_table = JosaTable::new();
+  let eul = josa_table.intern("을");
+  let main = Program {
+    const_table: vec![Constant::Integer(21)],
+    code: instructions(vec![
+      Opcode::Push(0),
+      Opcode::LoadGlobal(0),
+      Opcode::Call(vec![eul]),
+      Opcode::StoreGlobal(1),
+    ]),
+    global_var_names: vec![String::from("a두배"), String::from("결과")],
+    josa_table,
+    imports: vec![
+      Import {
+        module: String::from("a"),
+        names: vec![String::from("a두배")],
+      },
+      Import {
+        module: String::from("외부"),
+        names: vec![String::from("없는_값")],
+      },
+    ],
+    exports: Vec::new(),
+  };
+
+  // base 모듈은 a 모듈보다 뒤에 주어져도 먼저 실행되어야 합니다.
+  let units = vec![unit("main", main), module("a"), module("base"), module("b")];
+  let mut linked = link(units).unwrap();
+
+  assert_eq!(
+    linked.global_var_names,
+    vec!["두배", "a두배", "결과", "b두배"]
+  );
+  assert_eq!(linked.exports, vec!["두배", "a두배", "b두배"]);
+  assert_eq!(
+    linked.imports,
+    vec![Import {
+      module: String::from("외부"),
+      names: vec![String::from("없는_값")],
+    }]
+  );
+
+  // 함께 링크하지 않은 모듈에서 가져오는 것은 실행하지 않고 지웁니다.
+  linked.imports.clear();
+  let encoded = encode(&linked).unwrap();
+  assert_eq!(
+    run(program(&encoded[..]).unwrap().1),
+    Some(Constant::Integer(42))
+  );
+}
+
+#[test]
+fn renumber_nested_functions() {
+  let base = Program {
+    const_table: vec![Constant::Integer(40)],
+    code: instructions(vec![Opcode::Push(0), Opcode::StoreGlobal(0)]),
+    global_var_names: vec![String::from("기본")],
+    josa_table: JosaTable::new(),
+    imports: Vec::new(),
+    exports: vec![String::from("기본")],
+  };
+
+  // 함수 안의 `Push`와 `LoadGlobal`도 합친 프로그램의 번호를 가리켜야 합니다.
+  let add_base = Constant::Function {
+    josa_map: Rc::new(JosaMap::new(Vec::new())),
+    rest_args: None,
+    func_object: FuncObject::CodeObject {
+      code: Rc::new(instructions(vec![
+        Opcode::LoadGlobal(1),
+        Opcode::Push(1),
+        Opcode::BinaryOp(BinaryOp::Add),
+      ])),
+      const_table: Rc::new(vec![Constant::Integer(1), Constant::Integer(2)]),
+      free_vars: Rc::default(),
+      caches: Rc::default(),
+    },
+  };
+  let main = Program {
+    const_table: vec![Constant::Integer(40), add_base],
+    code: instructions(vec![
+      Opcode::Push(1),
+      Opcode::Call(vec![]),
+      Opcode::StoreGlobal(0),
+    ]),
+    global_var_names: vec![String::from("결과"), String::from("기본")],
+    josa_table: JosaTable::new(),
+    imports: vec![Import {
+      module: String::from("base"),
+      names: vec![String::from("기본")],
+    }],
+    exports: Vec::new(),
+  };
+
+  let linked = link(vec![unit("main", main), unit("base", base)]).unwrap();
+  assert_eq!(linked.global_var_names, vec!["기본", "결과"]);
+  assert_eq!(linked.const_table.len(), 2);
+  assert_eq!(run(linked), Some(Constant::Integer(42)));
+}
+
+#[test]
+fn keep_private_globals_apart() {
+  // 두 모듈이 내보내지 않는 `도우미`에 각자 값을 넣습니다.
+  let first = Program {
+    const_table: vec![Constant::Integer(1)],
+    code: instructions(vec![
+      Opcode::Push(0),
+      Opcode::StoreGlobal(0),
+      Opcode::LoadGlobal(0),
+      Opcode::StoreGlobal(1),
+    ]),
+    global_var_names: vec![String::from("도우미"), String::from("첫째")],
+    josa_table: JosaTable::new(),
+    imports: Vec::new(),
+    exports: vec![String::from("첫째")],
+  };
+  let second = Program {
+    const_table: vec![Constant::Integer(2)],
+    code: instructions(vec![
+      Opcode::Push(0),
+      Opcode::StoreGlobal(0),
+      Opcode::LoadGlobal(0),
+      Opcode::LoadGlobal(1),
+      Opcode::BinaryOp(BinaryOp::Add),
+      Opcode::StoreGlobal(2),
+    ]),
+    global_var_names: vec![
+      String::from("도우미"),
+      String::from("첫째"),
+      String::from("결과"),
+    ],
+    josa_table: JosaTable::new(),
+    imports: vec![Import {
+      module: String::from("first"),
+      names: vec![String::from("첫째")],
+    }],
+    exports: Vec::new(),
+  };
+
+  let linked = link(vec![unit("second", second), unit("first", first)]).unwrap();
+  assert_eq!(
+    linked.global_var_names,
+    vec!["first::도우미", "첫째", "second::도우미", "결과"]
+  );
+  assert_eq!(run(linked), Some(Constant::Integer(3)));
+}
+
+#[test]
+fn report_link_errors() {
+  let other = load("tests/fixtures/modules/base.hnc");
+  match link(vec![module("base"), unit("other", other)]) {
+    Err(LinkError::ConflictingDefinition { name, modules }) => {
+      assert_eq!(name, "두배");
+      assert_eq!(modules, (String::from("base"), String::from("other")));
+    }
+    _ => panic!("같은 전역 변수를 두 번 정의했다는 에러가 나야 합니다."),
+  }
+
+  match link(vec![module("cycle_a"), module("cycle_b")]) {
+    Err(LinkError::ImportCycle { cycle }) => {
+      assert_eq!(cycle, vec!["cycle_a", "cycle_b", "cycle_a"])
+    }
+    _ => panic!("순환하여 가져온다는 에러가 나야 합니다."),
+  }
+
+  let mut a = load("tests/fixtures/modules/a.hnc");
+  a.imports[0].names = vec![String::from("세배")];
+  assert!(matches!(
+    link(vec![unit("a", a), module("base")]),
+    Err(LinkError::MissingExport { module, name }) if module == "base" && name == "세배"
+  ));
+}
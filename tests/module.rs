@@ -0,0 +1,220 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use haneul::constant::Constant;
+use haneul::error::HaneulError;
+use haneul::funcobject::FuncObject;
+use haneul::instruction::Instruction;
+use haneul::io::MemoryOutput;
+use haneul::josa::JosaTable;
+use haneul::machine::{Machine, StackFrame};
+use haneul::module::ModuleLoader;
+use haneul::opcode::Opcode;
+use haneul::program::{Import, Program};
+
+fn loader() -> ModuleLoader {
+  ModuleLoader::new(vec![PathBuf::from("tests/fixtures/modules")])
+}
+
+/// `imports`에서 가져온 이름과 `결과`를 전역 변수로 가진 프로그램입니다.
+fn importing(imports: Vec<(&str, Vec<&str>)>, code: Vec<Opcode>, josa_table: JosaTable) -> Program {
+  let mut global_var_names: Vec<String> = imports
+    .iter()
+    .flat_map(|(_, names)| names.iter().map(|name| name.to_string()))
+    .collect();
+  global_var_names.push(String::from("결과"));
+
+  Program {
+    const_table: vec![Constant::Integer(21)],
+    code: code
+      .into_iter()
+      .map(|opcode| Instruction {
+        line_number: 1,
+        opcode,
+      })
+      .collect(),
+    global_var_names,
+    josa_table,
+    imports: imports
+      .into_iter()
+      .map(|(module, names)| Import {
+        module: module.to_string(),
+        names: names.into_iter().map(String::from).collect(),
+      })
+      .collect(),
+    exports: Vec::new(),
+  }
+}
+
+fn code_of(value: &Constant) -> Rc<Vec<Instruction>> {
+  match value {
+    Constant::Function {
+      func_object: FuncObject::CodeObject { code, .. },
+      ..
+    } => code.clone(),
+    _ => panic!("함수가 아닙니다."),
+  }
+}
+
+#[test]
+fn load_each_module_once() {
+  let mut josa_table = JosaTable::new();
+  let eul = josa_table.intern("을");
+  let code = vec![
+    Opcode::Push(0),
+    Opcode::LoadGlobal(0),
+    Opcode::Call(vec![eul]),
+    Opcode::StoreGlobal(2),
+  ];
+  let mut program = importing(
+    vec![("a", vec!["a두배"]), ("b", vec!["b두배"])],
+    code,
+    josa_table,
+  );
+
+  let globals = loader().globals(&mut program).unwrap();
+  let mut machine = Machine::new(
+    globals,
+    program.global_var_names.clone(),
+    program.josa_table.clone(),
+  );
+  assert!(machine
+    .run(StackFrame::new(program.code, program.const_table))
+    .is_ok());
+  assert_eq!(machine.global("결과"), Some(Some(&Constant::Integer(42))));
+
+  // 두 모듈이 가져간 base 모듈의 함수는 한 번 실행해서 만든 같은 함수입니다.
+  let a = code_of(machine.global("a두배").unwrap().unwrap());
+  let b = code_of(machine.global("b두배").unwrap().unwrap());
+  assert!(Rc::ptr_eq(&a, &b));
+}
+
+#[test]
+fn reuse_loader_for_programs_with_different_josa() {
+  let mut loader = loader();
+  let call_a = |eul| {
+    vec![
+      Opcode::Push(0),
+      Opcode::LoadGlobal(0),
+      Opcode::Call(vec![eul]),
+      Opcode::StoreGlobal(1),
+    ]
+  };
+
+  // 두 프로그램에서 `을`의 번호가 서로 다릅니다.
+  let mut first_table = JosaTable::new();
+  let eul = first_table.intern("을");
+  let mut second_table = JosaTable::new();
+  second_table.intern("에");
+  second_table.intern("로");
+  let second_eul = second_table.intern("을");
+  assert_ne!(eul, second_eul);
+
+  for (josa_table, eul) in [(first_table, eul), (second_table, second_eul)] {
+    let mut program = importing(vec![("a", vec!["a두배"])], call_a(eul), josa_table);
+    let globals = loader.globals(&mut program).unwrap();
+    let mut machine = Machine::new(
+      globals,
+      program.global_var_names.clone(),
+      program.josa_table.clone(),
+    );
+    assert!(machine
+      .run(StackFrame::new(program.code, program.const_table))
+      .is_ok());
+    assert_eq!(machine.global("결과"), Some(Some(&Constant::Integer(42))));
+  }
+}
+
+#[test]
+fn run_exported_functions_with_module_globals() {
+  let mut josa_table = JosaTable::new();
+  let eul = josa_table.intern("을");
+  let code = vec![
+    Opcode::Push(0),
+    Opcode::LoadGlobal(0),
+    Opcode::Call(vec![eul]),
+    Opcode::StoreGlobal(1),
+  ];
+
+  // 내보낸 함수는 불러오는 프로그램의 전역 변수가 아니라 자기 모듈의 전역 변수를 읽고,
+  // 자기 모듈의 전역 변수 이름으로 자기 자신을 다시 부릅니다.
+  for (module, name, argument, expected) in [
+    ("secret", "비밀_읽기", 21, 100),
+    ("factorial", "팩토리얼", 5, 120),
+  ] {
+    let mut program = importing(vec![(module, vec![name])], code.clone(), josa_table.clone());
+    program.const_table = vec![Constant::Integer(argument)];
+
+    let globals = loader().globals(&mut program).unwrap();
+    let mut machine = Machine::new(
+      globals,
+      program.global_var_names.clone(),
+      program.josa_table.clone(),
+    );
+    assert!(machine
+      .run(StackFrame::new(program.code, program.const_table))
+      .is_ok());
+    assert_eq!(
+      machine.global("결과"),
+      Some(Some(&Constant::Integer(expected)))
+    );
+  }
+}
+
+#[test]
+fn report_import_errors() {
+  let mut program = importing(vec![("cycle_a", vec!["가"])], vec![], JosaTable::new());
+  match loader().globals(&mut program) {
+    Err(HaneulError::ImportCycle { cycle }) => {
+      assert_eq!(cycle, vec!["cycle_a", "cycle_b", "cycle_a"])
+    }
+    _ => panic!("순환하여 가져온다는 에러가 나야 합니다."),
+  }
+
+  let mut program = importing(vec![("base", vec!["세배"])], vec![], JosaTable::new());
+  assert!(matches!(
+    loader().globals(&mut program),
+    Err(HaneulError::MissingExport { module, name }) if module == "base" && name == "세배"
+  ));
+
+  let mut program = importing(vec![("broken", vec!["값"])], vec![], JosaTable::new());
+  assert!(matches!(
+    loader().globals(&mut program),
+    Err(HaneulError::MissingExport { module, .. }) if module == "broken"
+  ));
+
+  let mut program = importing(vec![("없는_모듈", vec![])], vec![], JosaTable::new());
+  assert!(matches!(
+    loader().globals(&mut program),
+    Err(HaneulError::ModuleNotFound { module }) if module == "없는_모듈"
+  ));
+}
+
+#[test]
+fn run_modules_with_host_io_and_limits() {
+  let output = MemoryOutput::new();
+  let mut loader = loader();
+  loader.set_output(Box::new(output.clone()));
+  loader.set_input(Box::new(Cursor::new("하늘\n")));
+  loader.set_fuel(Some(100));
+
+  let mut program = importing(vec![("greeting", vec!["인사"])], vec![], JosaTable::new());
+  let globals = loader.globals(&mut program).unwrap();
+  let index = program
+    .global_var_names
+    .iter()
+    .position(|x| x == "인사")
+    .unwrap();
+  assert_eq!(globals[index], Some(Constant::Str("하늘".into())));
+  assert_eq!(output.contents(), "안녕\n");
+  assert_eq!(loader.fuel(), Some(93));
+
+  let mut program = importing(vec![("forever", vec![])], vec![], JosaTable::new());
+  assert!(matches!(
+    loader.globals(&mut program),
+    Err(HaneulError::ModuleFailed { module, reason })
+      if module == "forever" && reason.contains("실행 한도")
+  ));
+  assert_eq!(loader.fuel(), Some(0));
+}
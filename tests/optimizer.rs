@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use haneul::builtin::get_builtin;
+use haneul::constant::Constant;
+use haneul::funcobject::FuncObject;
+use haneul::instruction::Instruction;
+use haneul::josa::{JosaMap, JosaTable};
+use haneul::machine::{Machine, StackFrame};
+use haneul::opcode::{BinaryOp, Opcode, UnaryOp};
+use haneul::optimizer::optimize;
+use haneul::parser::program;
+use haneul::program::Program;
+
+const GLOBALS: [&str; 3] = ["가", "나", "다"];
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+  Finished { globals: Vec<String>, executed: u64 },
+  Error(u32, String),
+  Panic,
+}
+
+fn run(program: &Program) -> Outcome {
+  let mut josa_table = program.josa_table.clone();
+  let builtin = get_builtin(&mut josa_table, &program.global_var_names);
+  let mut machine = Machine::new(builtin, program.global_var_names.clone(), josa_table);
+  let frame = StackFrame::new(program.code.clone(), program.const_table.clone());
+
+  let result = panic::catch_unwind(AssertUnwindSafe(|| {
+    machine
+      .run(frame)
+      .map_err(|(line_number, err)| (line_number, format!("{:?}", err)))
+  }));
+
+  match result {
+    Ok(Ok(())) => Outcome::Finished {
+      globals: program
+        .global_var_names
+        .iter()
+        .map(|name| format!("{:?}", machine.global(name)))
+        .collect(),
+      executed: machine.executed_instructions(),
+    },
+    Ok(Err((line_number, err))) => Outcome::Error(line_number, err),
+    Err(_) => Outcome::Panic,
+  }
+}
+
+/// 최적화한 프로그램이 원래 프로그램과 같은 결과를 내고, 명령어를 더 많이 실행하지 않는지 확인합니다.
+fn assert_same_behavior(original: Program) {
+  let expected = run(&original);
+  let optimized = optimize(original.clone());
+  let actual = run(&optimized);
+
+  match (&expected, &actual) {
+    (
+      Outcome::Finished {
+        globals: expected_globals,
+        executed: expected_executed,
+      },
+      Outcome::Finished {
+        globals: actual_globals,
+        executed: actual_executed,
+      },
+    ) => {
+      assert_eq!(expected_globals, actual_globals, "{:#?}", original);
+      assert!(actual_executed <= expected_executed, "{:#?}", original);
+    }
+    _ => assert_eq!(expected, actual, "{:#?}", original),
+  }
+}
+
+/// 무작위로 스택 코드를 만들어내는 간단한 생성기입니다.
+struct Generator {
+  seed: u64,
+  line_number: u32,
+  code: Vec<Instruction>,
+  const_table: Vec<Constant>,
+  josa_table: JosaTable,
+}
+
+impl Generator {
+  fn new(seed: u64) -> Generator {
+    Generator {
+      seed,
+      line_number: 1,
+      code: Vec::new(),
+      const_table: Vec::new(),
+      josa_table: JosaTable::new(),
+    }
+  }
+
+  fn next(&mut self, bound: u64) -> u64 {
+    self.seed = self
+      .seed
+      .wrapping_mul(6364136223846793005)
+      .wrapping_add(1442695040888963407);
+    (self.seed >> 33) % bound
+  }
+
+  fn emit(&mut self, opcode: Opcode) -> usize {
+    self.code.push(Instruction {
+      line_number: self.line_number,
+      opcode,
+    });
+    self.code.len() - 1
+  }
+
+  fn patch(&mut self, index: usize) {
+    let target = self.code.len() as u32;
+    match &mut self.code[index].opcode {
+      Opcode::Jmp(v) | Opcode::PopJmpIfFalse(v) => *v = target,
+      _ => unreachable!(),
+    }
+  }
+
+  fn push(&mut self, value: Constant) {
+    self.const_table.push(value);
+    let index = self.const_table.len() as u32 - 1;
+    self.emit(Opcode::Push(index));
+  }
+
+  fn leaf(&mut self) {
+    match self.next(10) {
+      0..=4 => {
+        let value = self.next(7) as i64 - 3;
+        self.push(Constant::Integer(value))
+      }
+      5 | 6 => {
+        let value = (self.next(9) as f64 - 4.0) / 2.0;
+        self.push(Constant::Real(value))
+      }
+      7 | 8 => {
+        let index = self.next(GLOBALS.len() as u64) as u32;
+        self.emit(Opcode::LoadGlobal(index));
+      }
+      _ => {
+        let value = self.next(2) == 0;
+        self.push(Constant::Boolean(value))
+      }
+    }
+  }
+
+  fn expression(&mut self, depth: u32) {
+    if depth == 0 {
+      return self.leaf();
+    }
+
+    match self.next(8) {
+      0 | 1 => self.leaf(),
+      2 => {
+        self.expression(depth - 1);
+        self.emit(Opcode::UnaryOp(UnaryOp::Negate));
+      }
+      3 => {
+        // 0으로 나누지 않도록 나누는 수는 0이 아닌 상수로 정합니다.
+        self.expression(depth - 1);
+        let value = self.next(3) as i64 + 1;
+        if self.next(2) == 0 {
+          self.push(Constant::Integer(value));
+          self.emit(Opcode::BinaryOp(BinaryOp::Mod));
+        } else {
+          self.push(Constant::Real(value as f64));
+          self.emit(Opcode::BinaryOp(BinaryOp::Divide));
+        }
+      }
+      _ => {
+        self.expression(depth - 1);
+        self.expression(depth - 1);
+        let op = match self.next(6) {
+          0 => BinaryOp::Add,
+          1 => BinaryOp::Subtract,
+          2 => BinaryOp::Multiply,
+          3 => BinaryOp::Cmp(Ordering::Less),
+          4 => BinaryOp::Cmp(Ordering::Greater),
+          _ => BinaryOp::Cmp(Ordering::Equal),
+        };
+        self.emit(Opcode::BinaryOp(op));
+      }
+    }
+  }
+
+  fn function(&mut self) -> Constant {
+    let eul = self.josa_table.intern("을");
+
+    let mut body = Generator::new(self.next(u64::MAX));
+    body.line_number = self.line_number;
+    body.expression(2);
+    body.emit(Opcode::Load(0));
+    body.emit(Opcode::BinaryOp(BinaryOp::Add));
+
+    Constant::Function {
+      josa_map: Rc::new(JosaMap::new(vec![eul])),
+      rest_args: None,
+      func_object: FuncObject::CodeObject {
+        code: Rc::new(body.code),
+        const_table: Rc::new(body.const_table),
+        free_vars: Rc::default(),
+        caches: Rc::default(),
+      },
+    }
+  }
+
+  fn statement(&mut self, depth: u32) {
+    self.line_number += 1;
+
+    match self.next(10) {
+      0..=3 => {
+        self.expression(3);
+        let index = self.next(GLOBALS.len() as u64) as u32;
+        self.emit(Opcode::StoreGlobal(index));
+      }
+      4 => {
+        self.expression(2);
+        self.emit(Opcode::Pop);
+      }
+      5 => {
+        let index = self.emit(Opcode::Jmp(0));
+        self.leaf();
+        self.emit(Opcode::Pop);
+        self.patch(index);
+      }
+      6 => {
+        self.expression(1);
+        let function = self.function();
+        self.push(function);
+        let eul = self.josa_table.intern("을");
+        self.emit(Opcode::Call(vec![eul]));
+        let index = self.next(GLOBALS.len() as u64) as u32;
+        self.emit(Opcode::StoreGlobal(index));
+      }
+      _ if depth > 0 => {
+        self.expression(2);
+        let else_jump = self.emit(Opcode::PopJmpIfFalse(0));
+        self.block(depth - 1);
+        let end_jump = self.emit(Opcode::Jmp(0));
+        self.patch(else_jump);
+        self.block(depth - 1);
+        self.patch(end_jump);
+      }
+      _ => {
+        self.leaf();
+        self.emit(Opcode::Pop);
+      }
+    }
+  }
+
+  fn block(&mut self, depth: u32) {
+    for _ in 0..self.next(3) + 1 {
+      self.statement(depth);
+    }
+  }
+
+  fn program(mut self) -> Program {
+    for index in 0..GLOBALS.len() as u32 {
+      self.push(Constant::Integer(0));
+      self.emit(Opcode::StoreGlobal(index + 1));
+    }
+    self.block(2);
+
+    let mut global_var_names = vec![String::from("출력")];
+    global_var_names.extend(GLOBALS.iter().map(|x| x.to_string()));
+
+    // 전역 변수 0번은 출력 함수이므로 나머지 번호를 한 칸씩 미룹니다.
+    let mut code = self.code;
+    for inst in &mut code[GLOBALS.len() * 2..] {
+      match &mut inst.opcode {
+        Opcode::LoadGlobal(v) | Opcode::StoreGlobal(v) => *v += 1,
+        _ => {}
+      }
+    }
+
+    Program {
+      const_table: self.const_table,
+      code,
+      global_var_names,
+      josa_table: self.josa_table,
+      imports: Vec::new(),
+      exports: Vec::new(),
+    }
+  }
+}
+
+#[test]
+fn random_programs_behave_the_same() {
+  let default_hook = panic::take_hook();
+  panic::set_hook(Box::new(|_| {}));
+
+  for seed in 0..500 {
+    assert_same_behavior(Generator::new(seed).program());
+  }
+
+  panic::set_hook(default_hook);
+}
+
+#[test]
+fn fixture_behaves_the_same() {
+  let data = fs::read("tests/fixtures/double.hnc").unwrap();
+  let (_, parsed) = program(&data[..]).unwrap();
+  assert_same_behavior(parsed);
+}
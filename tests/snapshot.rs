@@ -0,0 +1,162 @@
+use std::fs;
+use std::rc::Rc;
+
+use haneul::builtin::{builtin_functions, get_builtin};
+use haneul::constant::Constant;
+use haneul::funcobject::FuncObject;
+use haneul::instruction::Instruction;
+use haneul::josa::{JosaMap, JosaTable};
+use haneul::machine::{Machine, StackFrame};
+use haneul::opcode::{BinaryOp, Opcode};
+use haneul::parser::program;
+use haneul::program::Program;
+use haneul::snapshot::{Snapshot, SnapshotError};
+
+fn load(path: &str) -> Program {
+  let data = fs::read(path).unwrap();
+  program(&data[..]).unwrap().1
+}
+
+fn new_machine(program: &Program) -> Machine {
+  let mut josa_table = program.josa_table.clone();
+  let builtin = get_builtin(&mut josa_table, &program.global_var_names);
+  Machine::new(builtin, program.global_var_names.clone(), josa_table)
+}
+
+fn instructions(code: Vec<Opcode>) -> Vec<Instruction> {
+  code
+    .into_iter()
+    .map(|opcode| Instruction {
+      line_number: 1,
+      opcode,
+    })
+    .collect()
+}
+
+fn function(code: Vec<Opcode>, const_table: Vec<Constant>) -> Constant {
+  Constant::Function {
+    josa_map: Rc::new(JosaMap::new(Vec::new())),
+    rest_args: None,
+    func_object: FuncObject::CodeObject {
+      code: Rc::new(instructions(code)),
+      const_table: Rc::new(const_table),
+      free_vars: Rc::default(),
+      caches: Rc::default(),
+    },
+  }
+}
+
+fn globals(machine: &Machine) -> Vec<String> {
+  machine
+    .global_var_names()
+    .iter()
+    .map(|name| format!("{:?}", machine.global(name).unwrap()))
+    .collect()
+}
+
+/// `chunk`개의 명령어마다 멈춰서 바이트로 저장하고, 새 가상 머신으로 되살려 이어서 실행합니다.
+fn run_with_snapshots(program: &Program, chunk: u64) -> (Vec<String>, usize) {
+  let natives = builtin_functions(&mut JosaTable::new());
+  let mut machine = new_machine(program);
+  machine.set_fuel(Some(chunk));
+  machine.load(StackFrame::new(
+    program.code.clone(),
+    program.const_table.clone(),
+  ));
+
+  let mut max_depth = 0;
+  while machine.resume().is_err() {
+    max_depth = max_depth.max(machine.call_stack().len());
+
+    let bytes = machine.snapshot().to_bytes();
+    machine = Machine::from_snapshot(Snapshot::from_bytes(&bytes, &natives).unwrap());
+    machine.add_fuel(chunk);
+  }
+
+  (globals(&machine), max_depth)
+}
+
+#[test]
+fn resume_from_snapshots() {
+  for path in &[
+    "tests/fixtures/fibonacci.hnc",
+    "tests/fixtures/partial_sum.hnc",
+    "tests/fixtures/closure.hnc",
+  ] {
+    let program = load(path);
+
+    let mut machine = new_machine(&program);
+    let frame = StackFrame::new(program.code.clone(), program.const_table.clone());
+    assert!(machine.run(frame).is_ok());
+    let expected = globals(&machine);
+
+    let (result, max_depth) = run_with_snapshots(&program, 997);
+    assert_eq!(result, expected, "{}", path);
+    assert!(max_depth > 1, "{}", path);
+  }
+}
+
+#[test]
+fn restore_natives_by_name() {
+  let program = load("tests/fixtures/closure.hnc");
+  let mut machine = new_machine(&program);
+  machine.set_fuel(Some(20));
+  let frame = StackFrame::new(program.code.clone(), program.const_table.clone());
+  assert!(machine.run(frame).is_err());
+  let bytes = machine.snapshot().to_bytes();
+
+  let natives = builtin_functions(&mut JosaTable::new());
+  let restored = Machine::from_snapshot(Snapshot::from_bytes(&bytes, &natives).unwrap());
+  assert!(matches!(
+    restored.global("출력"),
+    Some(Some(Constant::Function { .. }))
+  ));
+  assert_eq!(restored.executed_instructions(), 20);
+
+  match Snapshot::from_bytes(&bytes, &[]) {
+    Err(SnapshotError::UnknownNative { name }) => assert_eq!(name, "출력"),
+    _ => panic!("내장 함수를 찾지 못했다는 에러가 나야 합니다."),
+  }
+  assert!(matches!(
+    Snapshot::from_bytes(&bytes[..bytes.len() - 1], &natives),
+    Err(SnapshotError::InvalidFormat { .. })
+  ));
+}
+
+#[test]
+fn resume_from_tail_call() {
+  // `꼬리`는 마지막 명령어로 `값`을 호출하므로, `값`을 실행하는 동안 `꼬리` 프레임의 ip는 코드의 끝입니다.
+  let value = function(
+    vec![
+      Opcode::Push(0),
+      Opcode::Push(1),
+      Opcode::BinaryOp(BinaryOp::Add),
+    ],
+    vec![Constant::Integer(40), Constant::Integer(2)],
+  );
+  let tail = function(vec![Opcode::LoadGlobal(1), Opcode::Call(vec![])], vec![]);
+  let program = Program {
+    const_table: vec![tail, value],
+    code: instructions(vec![
+      Opcode::Push(0),
+      Opcode::StoreGlobal(0),
+      Opcode::Push(1),
+      Opcode::StoreGlobal(1),
+      Opcode::LoadGlobal(0),
+      Opcode::Call(vec![]),
+      Opcode::StoreGlobal(2),
+    ]),
+    global_var_names: vec![
+      String::from("꼬리"),
+      String::from("값"),
+      String::from("결과"),
+    ],
+    josa_table: JosaTable::new(),
+    imports: Vec::new(),
+    exports: Vec::new(),
+  };
+
+  let (result, max_depth) = run_with_snapshots(&program, 1);
+  assert_eq!(max_depth, 3);
+  assert_eq!(result[2], format!("{:?}", Some(&Constant::Integer(42))));
+}